@@ -0,0 +1,131 @@
+use crate::*;
+
+/// A single player move, applied with [`Game::apply`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// See [`Game::open`]
+    Open(Ix2),
+    /// See [`Game::flag`]
+    Flag(Ix2),
+    /// See [`Game::chord_open`]
+    ChordOpen(Ix2),
+    /// See [`Game::chord_flag`]
+    ChordFlag(Ix2),
+    /// See [`Game::flag_question`]
+    FlagQuestion(Ix2),
+}
+
+impl Action {
+    /// The tile the move targets
+    pub const fn coords(self) -> Ix2 {
+        use Action::*;
+        match self {
+            Open(coords) | Flag(coords) | ChordOpen(coords) | ChordFlag(coords)
+            | FlagQuestion(coords) => coords,
+        }
+    }
+}
+
+/// Outcome of [`Game::apply`], wraps the outcome of the method the action maps to
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionOutcome {
+    Open(OpenOutcome),
+    Flag(FlagOutcome),
+}
+
+impl ActionOutcome {
+    /// Whether this outcome could have caused an update to the game
+    pub const fn has_update(self) -> bool {
+        match self {
+            Self::Open(outcome) => outcome.has_update(),
+            Self::Flag(outcome) => outcome.has_update(),
+        }
+    }
+}
+
+impl Game {
+    /// Apply a move by calling the method it maps to, a deserialized action can point anywhere so it's checked against
+    /// the board first
+    pub fn apply(&mut self, action: Action, now: DateTime<Utc>) -> Result<ActionOutcome> {
+        use Action::*;
+        self.minefield.validate_coords(action.coords())?;
+        Ok(match action {
+            Open(coords) => ActionOutcome::Open(self.open(coords, now)?),
+            Flag(coords) => ActionOutcome::Flag(self.flag(coords)?),
+            ChordOpen(coords) => ActionOutcome::Open(self.chord_open(coords, now)?),
+            ChordFlag(coords) => ActionOutcome::Flag(self.chord_flag(coords)?),
+            FlagQuestion(coords) => ActionOutcome::Flag(self.flag_question(coords)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now};
+
+    #[test]
+    fn actions_route_to_their_method() {
+        let flag = |outcome| ActionOutcome::Flag(outcome);
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        assert_eq!(
+            game.apply(Action::Open((1, 0)), now()).unwrap(),
+            ActionOutcome::Open(OpenOutcome::Safe)
+        );
+        assert_eq!(game.tile_at((1, 0)), AnyTile::Open(1));
+        assert_eq!(
+            game.apply(Action::Flag((0, 0)), now()).unwrap(),
+            flag(FlagOutcome::MarkChanged)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+        assert_eq!(
+            game.apply(Action::FlagQuestion((0, 0)), now()).unwrap(),
+            flag(FlagOutcome::MarkChanged)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Question);
+        assert_eq!(
+            game.apply(Action::Flag((0, 0)), now()).unwrap(),
+            flag(FlagOutcome::MarkChanged)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+
+        game.apply(Action::Flag((0, 0)), now()).unwrap();
+        assert_eq!(
+            game.apply(Action::ChordOpen((1, 0)), now()).unwrap(),
+            ActionOutcome::Open(OpenOutcome::Safe)
+        );
+        assert!(game.is_open_at((1, 1)));
+
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.set_flood_fill(false);
+        for pos in [(1, 0), (2, 0), (0, 1), (1, 1), (2, 1)] {
+            game.open(pos, now()).unwrap();
+        }
+        assert_eq!(
+            game.apply(Action::ChordFlag((1, 0)), now()).unwrap(),
+            flag(FlagOutcome::MarkChanged)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+        assert_eq!(Action::ChordFlag((1, 0)).coords(), (1, 0));
+    }
+
+    #[test]
+    fn out_of_bounds_actions_are_rejected() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((1, 0), now()).unwrap();
+        for action in [
+            Action::Open((3, 0)),
+            Action::Flag((0, 3)),
+            Action::ChordOpen((3, 3)),
+            Action::ChordFlag((5, 1)),
+            Action::FlagQuestion((1, 9)),
+        ] {
+            assert!(matches!(
+                game.apply(action, now()),
+                Err(GameError::InvalidCoords)
+            ));
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::*;
+
+/// A game whose minefield is generated by the first move, with the tile of that move as the generator's start tile, so
+/// a generator like [`RandomMinefieldGenerator`] with [`StartTile::AlwaysZero`] makes every first open safe
+pub struct DeferredGame<F> {
+    config: GameConfig,
+    make_generator: F,
+    game: Option<Game>,
+}
+
+impl Game {
+    /// Start a game without a minefield yet, `make_generator` is called with the coords of the first move, see
+    /// [`DeferredGame`]
+    pub fn start<F, G>(config: GameConfig, make_generator: F) -> DeferredGame<F>
+    where
+        F: FnMut(Ix2) -> G,
+        G: MinefieldGenerator,
+    {
+        DeferredGame {
+            config,
+            make_generator,
+            game: None,
+        }
+    }
+}
+
+impl<F, G> DeferredGame<F>
+where
+    F: FnMut(Ix2) -> G,
+    G: MinefieldGenerator,
+{
+    pub fn config(&self) -> GameConfig {
+        self.config
+    }
+
+    /// The game, `None` until the first move
+    pub fn game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    pub fn game_mut(&mut self) -> Option<&mut Game> {
+        self.game.as_mut()
+    }
+
+    /// The game, generating the minefield with `coords` as the start tile if there was no move yet
+    pub fn get_or_generate(&mut self, coords: Ix2) -> Result<&mut Game> {
+        let size = self.config.size;
+        if coords.0 >= size.0 || coords.1 >= size.1 {
+            return Err(GameError::InvalidCoords);
+        }
+        let Self {
+            config,
+            make_generator,
+            game,
+        } = self;
+        Ok(game.get_or_insert_with(|| Game::new(make_generator(coords).generate(*config))))
+    }
+
+    /// Same as [`Game::open`], the first call generates the minefield
+    pub fn open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+        self.get_or_generate(coords)?.open(coords, now)
+    }
+
+    /// Same as [`Game::chord_open`], the first call generates the minefield
+    pub fn chord_open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+        self.get_or_generate(coords)?.chord_open(coords, now)
+    }
+
+    /// Drop the game, the next move generates a new minefield
+    pub fn restart(&mut self) {
+        self.game = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::now;
+
+    #[test]
+    fn first_open_is_never_a_mine() {
+        for seed in 0..50 {
+            for start in [(0, 0), (4, 4), (8, 3)] {
+                let mut game = Game::start(GameConfig::BEGINNER, |coords| {
+                    RandomMinefieldGenerator::new(seed, coords, StartTile::AlwaysZero)
+                });
+                assert!(game.game().is_none());
+                let outcome = game.open(start, now()).unwrap();
+                assert!(matches!(outcome, OpenOutcome::Safe | OpenOutcome::Win));
+                assert_eq!(game.game().unwrap().tile_at(start), AnyTile::Open(0));
+            }
+        }
+    }
+
+    #[test]
+    fn out_of_bounds_move_generates_nothing() {
+        let mut game = Game::start(GameConfig::BEGINNER, |coords| {
+            RandomMinefieldGenerator::new(0, coords, StartTile::AlwaysZero)
+        });
+        assert!(game.open((9, 0), now()).is_err());
+        assert!(game.game().is_none());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::*;
+
+/// Picks the next mine count from recent results, harder after wins and easier after losses, the longer the streak
+/// the bigger the step. The board size is kept.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveDifficulty {
+    /// Lowest density as (mines, tiles)
+    pub min_density: (Ax, Ax),
+    /// Highest density as (mines, tiles)
+    pub max_density: (Ax, Ax),
+    /// Longest streak that still makes the step bigger
+    pub max_streak: u8,
+}
+
+impl Default for AdaptiveDifficulty {
+    /// Between beginner (10 mines in 9×9) and evil (130 mines in 30×20) densities
+    fn default() -> Self {
+        Self {
+            min_density: (10, 81),
+            max_density: (130, 600),
+            max_streak: 3,
+        }
+    }
+}
+
+impl AdaptiveDifficulty {
+    /// Fewest and most mines allowed for a board of this size, never more than [`GameConfig::max_mines`]
+    pub fn mine_bounds(&self, config: GameConfig) -> (Ax, Ax) {
+        let total = u32::from(config.total_tiles());
+        let scale = |(mines, tiles): (Ax, Ax), round_up: bool| {
+            let (mines, tiles) = (u32::from(mines), u32::from(tiles).max(1));
+            let scaled = if round_up {
+                (total * mines).div_ceil(tiles)
+            } else {
+                total * mines / tiles
+            };
+            Ax::try_from(scaled).unwrap_or(Ax::MAX)
+        };
+        let max_mines = config.max_mines();
+        let max = scale(self.max_density, false).clamp(max_mines.min(1), max_mines);
+        let min = scale(self.min_density, true).clamp(max.min(1), max);
+        (min, max)
+    }
+
+    /// Next config given the results so far (`true` for a win), oldest first, with no results only the bounds apply
+    pub fn next_config(&self, config: GameConfig, wins: &[bool]) -> GameConfig {
+        let (min, max) = self.mine_bounds(config);
+        let mut mines = config.mines.clamp(min, max);
+        if let Some(&last) = wins.last() {
+            let streak = wins.iter().rev().take_while(|&&won| won == last).count();
+            let streak = streak.min(self.max_streak.into()) as Ax;
+            // one step is about one mine per beginner board worth of tiles
+            let step = streak * (config.total_tiles() / 81).max(1);
+            mines = if last {
+                mines.saturating_add(step).min(max)
+            } else {
+                mines.saturating_sub(step).max(min)
+            };
+        }
+        GameConfig::new(config.size, mines)
+    }
+}
+
+/// Coarse label for how hard a board is to clear from a given first move, see [`RatingThresholds`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum DifficultyRating {
+    /// The first move wins
+    Trivial,
+    Easy,
+    Medium,
+    Hard,
+    /// The built-in solvers get stuck at some point, or the first move isn't a safe tile on the board
+    RequiresGuess,
+}
+
+impl DifficultyRating {
+    pub fn as_str(&self) -> &'static str {
+        use DifficultyRating::*;
+        match self {
+            Trivial => "trivial",
+            Easy => "easy",
+            Medium => "medium",
+            Hard => "hard",
+            RequiresGuess => "requires_guess",
+        }
+    }
+}
+
+/// Thresholds of [`RatingThresholds::rate`], the board is played from the first move opening every tile proven safe
+/// and flagging every tile proven a mine. A step is one such round, it needs the subset rule when single-clue rules
+/// find nothing. The 3BV ratio is [`Minefield::bbbv`] as a percentage of the safe tiles.
+///
+/// - `Trivial`: the first move wins
+/// - `Easy`: up to `easy_max_subset_steps` subset steps and a 3BV ratio up to `easy_max_bbbv_percent`
+/// - `Medium`: up to `medium_max_subset_steps` subset steps and a 3BV ratio up to `medium_max_bbbv_percent`
+/// - `Hard`: anything else that is solved without guessing
+/// - `RequiresGuess`: the solvers get stuck before the board is cleared
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RatingThresholds {
+    pub easy_max_bbbv_percent: u8,
+    pub easy_max_subset_steps: u16,
+    pub medium_max_bbbv_percent: u8,
+    pub medium_max_subset_steps: u16,
+}
+
+impl Default for RatingThresholds {
+    /// Random beginner boards opened on a zero have a 3BV ratio around 20 percent and spread over easy to hard, expert
+    /// ones are around 45 percent and rate hard when they don't need a guess
+    fn default() -> Self {
+        Self {
+            easy_max_bbbv_percent: 20,
+            easy_max_subset_steps: 1,
+            medium_max_bbbv_percent: 35,
+            medium_max_subset_steps: 4,
+        }
+    }
+}
+
+impl RatingThresholds {
+    pub fn rate(&self, minefield: &Minefield, first_move: Ix2) -> DifficultyRating {
+        use DifficultyRating::*;
+
+        if minefield.validate_coords(first_move).is_err() {
+            return RequiresGuess;
+        }
+        // the timestamps don't matter here
+        let now = DateTime::<Utc>::default();
+        let mut game = Game::new(minefield.clone());
+        match game.open(first_move, now) {
+            Ok(OpenOutcome::Win) => return Trivial,
+            Ok(OpenOutcome::Safe) => {}
+            _ => return RequiresGuess,
+        }
+
+        let mut subset_steps: u16 = 0;
+        while !game.ended() {
+            let obs = Observation::from_game(&game);
+            let mut deductions = TrivialSolver.deduce(&obs);
+            if deductions.safe.is_empty() {
+                deductions = TrivialSolver.then(SubsetSolver).deduce(&obs);
+                if deductions.safe.is_empty() {
+                    return RequiresGuess;
+                }
+                subset_steps += 1;
+            }
+            for coords in deductions.mines {
+                if game.tile_at(coords) == AnyTile::Closed {
+                    let _ = game.flag(coords);
+                }
+            }
+            for coords in deductions.safe {
+                let _ = game.open(coords, now);
+            }
+        }
+        if !matches!(game.cur_state(), GameState::Win) {
+            // only reachable if a solver is wrong
+            return RequiresGuess;
+        }
+
+        let percent = u32::from(minefield.bbbv()) * 100 / u32::from(minefield.safe_count());
+        if subset_steps <= self.easy_max_subset_steps
+            && percent <= self.easy_max_bbbv_percent.into()
+        {
+            Easy
+        } else if subset_steps <= self.medium_max_subset_steps
+            && percent <= self.medium_max_bbbv_percent.into()
+        {
+            Medium
+        } else {
+            Hard
+        }
+    }
+}
+
+/// [`RatingThresholds::rate`] with the default thresholds
+pub fn rate_board(minefield: &Minefield, first_move: Ix2) -> DifficultyRating {
+    RatingThresholds::default().rate(minefield, first_move)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::field;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn wins_raise_and_losses_lower_mines() {
+        let difficulty = AdaptiveDifficulty::default();
+        let beginner = GameConfig::new((9, 9), 10);
+        assert_eq!(difficulty.mine_bounds(beginner), (10, 17));
+
+        let mut config = beginner;
+        let mut wins = Vec::new();
+        for expected in [11, 13, 16] {
+            wins.push(true);
+            config = difficulty.next_config(config, &wins);
+            assert_eq!(config.mines, expected);
+        }
+        wins.push(false);
+        assert_eq!(difficulty.next_config(config, &wins).mines, 15);
+        assert_eq!(difficulty.next_config(beginner, &[false, false]).mines, 10);
+
+        let expert = GameConfig::new((30, 16), 99);
+        assert_eq!(difficulty.mine_bounds(expert), (60, 104));
+        assert_eq!(difficulty.next_config(expert, &[false]).mines, 94);
+
+        let dense = AdaptiveDifficulty {
+            max_density: (99, 100),
+            ..difficulty
+        };
+        let small = GameConfig::new((3, 3), 8);
+        assert_eq!(dense.mine_bounds(small).1, 8);
+        assert!(dense.next_config(small, &[true, true]).is_valid());
+        let single = GameConfig::new((1, 1), 0);
+        assert_eq!(dense.mine_bounds(single), (0, 0));
+        assert!(dense.next_config(single, &[true]).is_valid());
+    }
+
+    #[test]
+    fn rates_trivial_and_guess_boards() {
+        let minefield = field(&["....", "....", "....", "...*"]);
+        assert_eq!(minefield.bbbv(), 1);
+        assert_eq!(rate_board(&minefield, (0, 0)), DifficultyRating::Trivial);
+        // opening the mine, or off the board
+        assert_eq!(
+            rate_board(&minefield, (3, 3)),
+            DifficultyRating::RequiresGuess
+        );
+        assert_eq!(
+            rate_board(&minefield, (9, 9)),
+            DifficultyRating::RequiresGuess
+        );
+        assert_eq!(field(&["*..", "...", "...", "..*"]).bbbv(), 2);
+    }
+
+    #[test]
+    fn thresholds_are_tunable() {
+        let minefield = field(&[".*.."]);
+        assert_eq!(rate_board(&minefield, (0, 0)), DifficultyRating::Hard);
+        let lenient = RatingThresholds {
+            easy_max_bbbv_percent: 70,
+            ..Default::default()
+        };
+        assert_eq!(lenient.rate(&minefield, (0, 0)), DifficultyRating::Easy);
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::boxed::Box;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,6 +9,31 @@ pub enum GameError {
     TooManyMines,
     #[error("Game already ended, no new moves are accepted")]
     AlreadyEnded,
+    #[error("Invalid board shape")]
+    InvalidBoardShape,
+    #[error("Mine count does not match the minefield")]
+    MineCountMismatch,
+    #[error("Invalid character in board layout")]
+    InvalidLayoutCharacter,
+    #[error("Game progress does not match its state")]
+    InconsistentProgress,
+    #[error("Flag on a safe tile")]
+    FlagOnSafeTile,
+    #[error("No board at that index")]
+    InvalidBoardIndex,
+    #[error("Invalid clue radius")]
+    InvalidClueRadius,
+    #[error("Replay move {0} is earlier than the one before")]
+    ReplayOutOfOrder(usize),
+    #[error("Replay move {index} was rejected: {source}")]
+    ReplayMoveRejected {
+        index: usize,
+        source: Box<GameError>,
+    },
+    #[error("Replay does not end in a win")]
+    ReplayNotWon,
+    #[error("Replay took {actual}s but claims {claimed}s")]
+    ReplayTimeMismatch { claimed: u32, actual: u32 },
 }
 
 pub type Result<T> = core::result::Result<T, GameError>;
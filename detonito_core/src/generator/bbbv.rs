@@ -0,0 +1,76 @@
+use super::*;
+
+/// A [`Minefield::bbbv`] within `min..=max`, see [`BbbvRangeGenerator`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BbbvRange {
+    pub min: Ax,
+    pub max: Ax,
+}
+
+impl Requirement for BbbvRange {
+    /// How far the 3BV is from the range
+    fn shortfall(&self, minefield: &Minefield, _start: Ix2) -> usize {
+        let bbbv = minefield.bbbv();
+        let distance = if bbbv < self.min {
+            self.min - bbbv
+        } else {
+            bbbv.saturating_sub(self.max)
+        };
+        distance.into()
+    }
+
+    fn warn_exhausted(&self, best: &Minefield, _start: Ix2, max_attempts: u32) {
+        log::warn!(
+            "No minefield with 3BV in {}..={} after {} attempts, using the closest with {}",
+            self.min,
+            self.max,
+            max_attempts,
+            best.bbbv()
+        );
+    }
+}
+
+/// Keeps generating random minefields until one has a [`Minefield::bbbv`] within the range, see [`RetryGenerator`]
+pub type BbbvRangeGenerator = RetryGenerator<BbbvRange>;
+
+impl BbbvRangeGenerator {
+    pub fn new(
+        inner: RandomMinefieldGenerator,
+        min_bbbv: Ax,
+        max_bbbv: Ax,
+        max_attempts: u32,
+    ) -> Self {
+        let range = BbbvRange {
+            min: min_bbbv,
+            max: max_bbbv,
+        };
+        Self::with_requirement(inner, range, max_attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bbbv_within_requested_range() {
+        for seed in 0..20 {
+            let inner = RandomMinefieldGenerator::new(seed, (4, 4), StartTile::AlwaysZero);
+            let minefield =
+                BbbvRangeGenerator::new(inner.clone(), 20, 24, 200).generate(GameConfig::BEGINNER);
+            assert!((20..=24).contains(&minefield.bbbv()));
+            // any board fits, the first attempt is what the inner generator gives
+            let minefield =
+                BbbvRangeGenerator::new(inner.clone(), 0, 1000, 5).generate(GameConfig::BEGINNER);
+            assert_eq!(minefield, inner.generate(GameConfig::BEGINNER));
+        }
+    }
+
+    #[test]
+    fn impossible_range_falls_back_to_closest() {
+        let inner = RandomMinefieldGenerator::new(0, (4, 4), StartTile::AlwaysZero);
+        let minefield = BbbvRangeGenerator::new(inner, 500, 600, 20).generate(GameConfig::BEGINNER);
+        assert!(minefield.bbbv() < 500);
+        assert_eq!(minefield.count, 10);
+    }
+}
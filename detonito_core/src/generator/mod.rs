@@ -1,10 +1,78 @@
 use crate::*;
+pub use bbbv::*;
+pub use opening::*;
 pub use random::*;
+pub use retry::*;
+pub(crate) use rng::below;
+pub use rng::SplitMix64;
 
+mod bbbv;
+mod opening;
 mod random;
+mod retry;
+mod rng;
 
 pub trait MinefieldGenerator {
     fn generate(self, config: GameConfig) -> Minefield;
+
+    /// Same as [`MinefieldGenerator::generate`] but also tells whether the board is what was asked for, so a front-end
+    /// can warn that a board meant to start on a zero may not
+    fn generate_reporting(self, config: GameConfig) -> (Minefield, GenerationOutcome)
+    where
+        Self: Sized,
+    {
+        (self.generate(config), GenerationOutcome::Exact)
+    }
+
+    /// Same as [`MinefieldGenerator::generate`] with the options of `params` replacing the generator's own, options a
+    /// generator has no use for are ignored
+    fn generate_from(self, params: GenerationParams) -> Minefield
+    where
+        Self: Sized,
+    {
+        self.generate(params.config)
+    }
+}
+
+/// What to generate: the config of the board plus options only some generators read, a bare [`GameConfig`] converts
+/// into params without options
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    pub config: GameConfig,
+    /// Attempts of the generators that retry until a board meets their requirement
+    pub max_attempts: Option<u32>,
+}
+
+impl GenerationParams {
+    pub const fn new(config: GameConfig) -> Self {
+        Self {
+            config,
+            max_attempts: None,
+        }
+    }
+
+    pub const fn with_max_attempts(self, max_attempts: u32) -> Self {
+        Self {
+            max_attempts: Some(max_attempts),
+            ..self
+        }
+    }
+}
+
+impl From<GameConfig> for GenerationParams {
+    fn from(config: GameConfig) -> Self {
+        Self::new(config)
+    }
+}
+
+/// How closely a generated board follows the generator's settings
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GenerationOutcome {
+    Exact,
+    /// The start tile is safe but not a zero, there was no room for a mine free neighborhood
+    FellBackToSafe,
+    /// No attempt met the requirement, the closest board was used
+    ExhaustedAttempts,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -13,3 +81,128 @@ pub enum StartTile {
     SimpleSafe,
     AlwaysZero,
 }
+
+/// Generation strategies a front-end can offer by name, all of them are seeded so a seed and first move always give the
+/// same board
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GeneratorKind {
+    /// Purely random, even the first move can be a mine
+    Random,
+    /// The first move is never a mine
+    SafeStart,
+    /// The first move opens a zero when the board has room for it
+    ZeroStart,
+}
+
+impl GeneratorKind {
+    pub const ALL: [Self; 3] = [Self::Random, Self::SafeStart, Self::ZeroStart];
+
+    /// Stable name, e.g. `"zero-start"`
+    pub const fn name(self) -> &'static str {
+        use GeneratorKind::*;
+        match self {
+            Random => "random",
+            SafeStart => "safe-start",
+            ZeroStart => "zero-start",
+        }
+    }
+
+    /// Inverse of [`GeneratorKind::name`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+
+    pub const fn start_tile(self) -> StartTile {
+        use GeneratorKind::*;
+        match self {
+            Random => StartTile::Random,
+            SafeStart => StartTile::SimpleSafe,
+            ZeroStart => StartTile::AlwaysZero,
+        }
+    }
+
+    /// Generator for a game whose first move is `start`
+    pub fn make(self, seed: u64, start: Ix2) -> RandomMinefieldGenerator {
+        RandomMinefieldGenerator::new(seed, start, self.start_tile())
+    }
+}
+
+/// Seed from user input: a decimal number is used as is, anything else is hashed with 64-bit FNV-1a, so a passphrase
+/// like `"sunflower"` always gives the same boards
+pub fn seed_from_str(s: &str) -> u64 {
+    if let Ok(seed) = s.parse() {
+        return seed;
+    }
+    fnv1a(s.bytes())
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and versions unlike the hashers of `core`
+pub(crate) fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+    bytes.into_iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seed_from_passphrase_or_number() {
+        assert_eq!(seed_from_str("hello"), 0xa430_d846_80aa_bd0b);
+        assert_eq!(seed_from_str("42"), 42);
+        assert_eq!(seed_from_str("18446744073709551615"), u64::MAX);
+        assert_ne!(seed_from_str("-1"), seed_from_str("1"));
+    }
+
+    #[test]
+    fn every_kind_makes_a_beginner_board() {
+        for kind in GeneratorKind::ALL {
+            assert_eq!(GeneratorKind::from_name(kind.name()), Some(kind));
+            let minefield = kind.make(7, (4, 4)).generate(GameConfig::BEGINNER);
+            minefield.validate().unwrap();
+            assert_eq!(minefield.game_config(), GameConfig::BEGINNER);
+            if kind != GeneratorKind::Random {
+                assert!(!minefield[(4, 4)]);
+            }
+        }
+        assert_eq!(GeneratorKind::from_name("daily"), None);
+    }
+
+    #[test]
+    fn generation_outcomes() {
+        use GenerationOutcome::*;
+
+        let generator = |start_tile| RandomMinefieldGenerator::new(3, (1, 1), start_tile);
+        let outcome = |start_tile, config| generator(start_tile).generate_reporting(config).1;
+        assert_eq!(outcome(StartTile::AlwaysZero, GameConfig::BEGINNER), Exact);
+        assert_eq!(outcome(StartTile::Random, GameConfig::BEGINNER), Exact);
+
+        let crowded = GameConfig::new_unchecked((3, 3), 4);
+        assert_eq!(outcome(StartTile::AlwaysZero, crowded), FellBackToSafe);
+        assert_eq!(outcome(StartTile::SimpleSafe, crowded), Exact);
+
+        // one mine less is placed, which leaves room for a safe start tile
+        let full = GameConfig::new_unchecked((3, 3), 9);
+        assert_eq!(outcome(StartTile::SimpleSafe, full), Exact);
+        assert_eq!(outcome(StartTile::AlwaysZero, full), FellBackToSafe);
+        assert_eq!(outcome(StartTile::Random, full), Exact);
+
+        let bbbv = BbbvRangeGenerator::new(generator(StartTile::AlwaysZero), 500, 600, 3);
+        assert_eq!(
+            bbbv.generate_reporting(GameConfig::BEGINNER).1,
+            ExhaustedAttempts
+        );
+        let bbbv = BbbvRangeGenerator::new(generator(StartTile::AlwaysZero), 0, 500, 3);
+        assert_eq!(bbbv.generate_reporting(crowded).1, FellBackToSafe);
+
+        let (minefield, _) =
+            generator(StartTile::AlwaysZero).generate_reporting(GameConfig::BEGINNER);
+        assert_eq!(
+            minefield,
+            generator(StartTile::AlwaysZero).generate(GameConfig::BEGINNER)
+        );
+    }
+}
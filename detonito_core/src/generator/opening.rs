@@ -0,0 +1,89 @@
+use super::*;
+
+/// Opening the start tile reveals at least this many tiles, see [`MinOpeningGenerator`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MinOpening(pub usize);
+
+impl Requirement for MinOpening {
+    /// How many tiles the opening is short of the minimum
+    fn shortfall(&self, minefield: &Minefield, start: Ix2) -> usize {
+        self.0.saturating_sub(minefield.opening_size(start))
+    }
+
+    fn warn_exhausted(&self, best: &Minefield, start: Ix2, max_attempts: u32) {
+        log::warn!(
+            "No minefield opening {} tiles from {:?} after {} attempts, using the largest with {}",
+            self.0,
+            start,
+            max_attempts,
+            best.opening_size(start)
+        );
+    }
+}
+
+/// Keeps generating random minefields until opening the start tile reveals at least `min_opening` tiles, so the first
+/// move of a beginner isn't a lone count, see [`RetryGenerator`]
+pub type MinOpeningGenerator = RetryGenerator<MinOpening>;
+
+impl MinOpeningGenerator {
+    pub fn new(inner: RandomMinefieldGenerator, min_opening: usize, max_attempts: u32) -> Self {
+        Self::with_requirement(inner, MinOpening(min_opening), max_attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now};
+
+    #[test]
+    fn opening_sizes() {
+        let minefield = field(&["*...", "....", "...*"]);
+        assert_eq!(minefield.opening_size((0, 0)), 0);
+        assert_eq!(minefield.opening_size((1, 0)), 1);
+        assert_eq!(minefield.opening_size((0, 2)), minefield.best_opening().1);
+    }
+
+    #[test]
+    fn first_click_opens_at_least_the_minimum() {
+        for seed in 0..30 {
+            let inner = RandomMinefieldGenerator::new(seed, (4, 4), StartTile::AlwaysZero);
+            let (minefield, outcome) =
+                MinOpeningGenerator::new(inner, 25, 100).generate_reporting(GameConfig::BEGINNER);
+            assert_eq!(outcome, GenerationOutcome::Exact, "seed {}", seed);
+            let mut game = Game::new(minefield);
+            game.open((4, 4), now()).unwrap();
+            assert!(game.open_count.0 >= 25);
+        }
+
+        let inner = RandomMinefieldGenerator::new(1, (4, 4), StartTile::AlwaysZero);
+        let (minefield, outcome) =
+            MinOpeningGenerator::new(inner, 80, 5).generate_reporting(GameConfig::BEGINNER);
+        assert_eq!(outcome, GenerationOutcome::ExhaustedAttempts);
+        assert!(minefield.opening_size((4, 4)) < 80);
+    }
+
+    #[test]
+    fn attempts_come_from_the_params() {
+        let config = GameConfig::EXPERT;
+        let inner = RandomMinefieldGenerator::new(3, (15, 8), StartTile::SimpleSafe);
+        let first = inner.clone().generate(config);
+        assert!(first.opening_size((15, 8)) < 40);
+        assert_eq!(
+            MinOpeningGenerator::new(inner.clone(), 40, 1).generate(config),
+            first
+        );
+        assert_eq!(
+            MinOpeningGenerator::new(inner.clone(), 40, 1).generate_from(config.into()),
+            first
+        );
+
+        let params = GenerationParams::new(config).with_max_attempts(500);
+        let minefield = MinOpeningGenerator::new(inner.clone(), 40, 1).generate_from(params);
+        assert!(minefield.opening_size((15, 8)) >= 40);
+        assert_eq!(
+            inner.generate_from(GenerationParams::new(config).with_max_attempts(9)),
+            first
+        );
+    }
+}
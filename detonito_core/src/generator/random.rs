@@ -2,6 +2,20 @@ use super::*;
 
 /// Generation strategy that can optionally try to make the starting tile zero or at least safe, but other than that is
 /// purely random.
+///
+/// Randomness comes from [`SplitMix64`] seeded with `seed`, so the same seed, config, start and start tile policy
+/// always give the same minefield:
+///
+/// ```
+/// use detonito_core::*;
+///
+/// let minefield = RandomMinefieldGenerator::new(42, (4, 4), StartTile::AlwaysZero).generate(GameConfig::BEGINNER);
+/// let mines: Vec<Ix2> = minefield.iter_cells().filter(|&(_, mine)| mine).map(|(coords, _)| coords).collect();
+/// assert_eq!(
+///     mines,
+///     [(8, 0), (0, 2), (1, 2), (2, 2), (7, 4), (1, 7), (2, 7), (2, 8), (5, 8), (6, 8)]
+/// );
+/// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct RandomMinefieldGenerator {
     seed: u64,
@@ -17,42 +31,64 @@ impl RandomMinefieldGenerator {
             start_tile,
         }
     }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn start(&self) -> Ix2 {
+        self.start
+    }
 }
 
 impl MinefieldGenerator for RandomMinefieldGenerator {
     fn generate(self, config: GameConfig) -> Minefield {
-        use rand::prelude::*;
+        self.generate_reporting(config).0
+    }
+
+    fn generate_reporting(self, config: GameConfig) -> (Minefield, GenerationOutcome) {
+        let mut rng = SplitMix64::new(self.seed);
+        self.generate_reporting_with(config, &mut rng)
+    }
+}
+
+impl RandomMinefieldGenerator {
+    /// Same as [`MinefieldGenerator::generate`] but draws from the given RNG instead of one seeded from `seed`, so
+    /// successive boards can continue the same stream
+    pub fn generate_with(&self, config: GameConfig, rng: &mut impl rand::Rng) -> Minefield {
+        self.generate_reporting_with(config, rng).0
+    }
+
+    /// Same as [`RandomMinefieldGenerator::generate_with`], also telling whether the start tile policy was kept
+    pub fn generate_reporting_with(
+        &self,
+        config: GameConfig,
+        rng: &mut impl rand::Rng,
+    ) -> (Minefield, GenerationOutcome) {
         use StartTile::*;
 
         let total_tiles = config.total_tiles();
 
-        // optimize for full boards
-        if config.mines >= total_tiles {
-            if config.mines > total_tiles {
-                log::warn!(
-                    "Minefield already full, generated anyway, requested {} but only fits {}",
-                    config.mines,
-                    total_tiles
-                );
-            }
-            return Minefield {
-                mines: Array2::from_elem(config.size.convert(), true),
-                count: config.mines,
-            };
+        // a board needs a safe tile to be winnable, see Minefield::validate
+        let mine_count = config.mines.min(total_tiles.saturating_sub(1));
+        if mine_count < config.mines {
+            log::warn!(
+                "Too many mines, requested {} but only {} fit",
+                config.mines,
+                mine_count
+            );
         }
 
         let actual_start_tile = match self.start_tile {
-            Random => Random,
-            SimpleSafe | AlwaysZero if config.mines + 1 > total_tiles => {
-                log::warn!("Cannot make start tile safe, fallback to random");
-                Random
-            }
-            SimpleSafe => SimpleSafe,
-            AlwaysZero if config.mines + 9 > total_tiles => {
+            AlwaysZero if mine_count + 9 > total_tiles => {
                 log::warn!("Cannot make start tile zero, fallback to simple safe");
                 SimpleSafe
             }
-            AlwaysZero => AlwaysZero,
+            start_tile => start_tile,
+        };
+        let outcome = match (self.start_tile, actual_start_tile) {
+            (AlwaysZero, SimpleSafe) => GenerationOutcome::FellBackToSafe,
+            _ => GenerationOutcome::Exact,
         };
         let mut mines: Array2<bool> = Array2::default(config.size.convert());
         let mut free_tiles = match actual_start_tile {
@@ -71,14 +107,13 @@ impl MinefieldGenerator for RandomMinefieldGenerator {
         };
         let mut mines_placed = 0;
 
-        let mut rng = SmallRng::seed_from_u64(self.seed);
         {
             let tiles = mines.as_slice_mut().expect("layout should be standard");
-            while mines_placed < config.mines {
+            while mines_placed < mine_count {
                 if free_tiles == 0 {
                     break;
                 }
-                let mut place: Ax = rng.gen_range(0..free_tiles);
+                let mut place = rng::below(rng, free_tiles.into()) as Ax;
                 for (i, tile) in tiles.iter_mut().enumerate() {
                     let i = i as Ax;
                     if *tile {
@@ -110,13 +145,90 @@ impl MinefieldGenerator for RandomMinefieldGenerator {
 
         // double check mine count
         let count = mines.iter().filter(|&&tile| tile).count() as Ax;
-        if count != config.mines {
+        if count != mine_count {
             log::warn!(
                 "Generated minefield count mismatch, actual: {}, requested: {}",
                 count,
-                config.mines
+                mine_count
             );
         }
-        Minefield { mines, count }
+        (Minefield { mines, count }, outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn generate_with_continues_the_stream() {
+        let generator = RandomMinefieldGenerator::new(0, (4, 4), StartTile::AlwaysZero);
+        let run = || {
+            let mut rng = SplitMix64::new(7);
+            [(); 3].map(|_| generator.generate_with(GameConfig::BEGINNER, &mut rng))
+        };
+        let boards = run();
+        assert_eq!(boards, run());
+        assert!(boards[0] != boards[1] && boards[1] != boards[2] && boards[0] != boards[2]);
+        assert_eq!(
+            generator.generate_with(GameConfig::BEGINNER, &mut SplitMix64::new(0)),
+            generator.clone().generate(GameConfig::BEGINNER)
+        );
+    }
+
+    #[test]
+    fn generated_boards_are_valid() {
+        let configs = [
+            GameConfig::BEGINNER,
+            GameConfig::new_unchecked((3, 3), 8),
+            GameConfig::new_unchecked((3, 3), 9),
+            GameConfig::new_unchecked((3, 3), 20),
+            GameConfig::new_unchecked((1, 1), 1),
+        ];
+        for config in configs {
+            for start_tile in [
+                StartTile::Random,
+                StartTile::SimpleSafe,
+                StartTile::AlwaysZero,
+            ] {
+                let minefield =
+                    RandomMinefieldGenerator::new(3, (0, 0), start_tile).generate(config);
+                minefield.validate().unwrap();
+                assert_eq!(
+                    minefield.game_config().mines,
+                    config.mines.min(config.total_tiles() - 1)
+                );
+                if start_tile != StartTile::Random {
+                    assert!(!minefield[(0, 0)]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn seeded_minefield_is_pinned() {
+        let minefield = RandomMinefieldGenerator::new(7, (0, 0), StartTile::SimpleSafe)
+            .generate(GameConfig::BEGINNER);
+        let mines: Vec<Ix2> = minefield
+            .iter_cells()
+            .filter(|&(_, mine)| mine)
+            .map(|(coords, _)| coords)
+            .collect();
+        assert_eq!(
+            mines,
+            [
+                (4, 1),
+                (8, 1),
+                (0, 2),
+                (1, 2),
+                (2, 2),
+                (5, 2),
+                (4, 3),
+                (3, 5),
+                (2, 8),
+                (3, 8)
+            ]
+        );
     }
 }
@@ -0,0 +1,72 @@
+use super::*;
+
+/// What the boards of a [`RetryGenerator`] have to meet
+pub trait Requirement {
+    /// How far the board is from meeting the requirement when opened at `start`, 0 when it does
+    fn shortfall(&self, minefield: &Minefield, start: Ix2) -> usize;
+
+    /// Log that every attempt missed, `best` is the board with the smallest shortfall
+    fn warn_exhausted(&self, best: &Minefield, start: Ix2, max_attempts: u32);
+}
+
+/// Keeps generating random minefields until one meets the requirement, all attempts draw from one RNG seeded like the
+/// inner generator, so the first attempt is what the inner generator alone would give. When every attempt misses the
+/// one with the smallest shortfall is returned.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetryGenerator<R> {
+    inner: RandomMinefieldGenerator,
+    requirement: R,
+    max_attempts: u32,
+}
+
+impl<R: Requirement> RetryGenerator<R> {
+    pub fn with_requirement(
+        inner: RandomMinefieldGenerator,
+        requirement: R,
+        max_attempts: u32,
+    ) -> Self {
+        Self {
+            inner,
+            requirement,
+            max_attempts,
+        }
+    }
+}
+
+impl<R: Requirement> MinefieldGenerator for RetryGenerator<R> {
+    fn generate(self, config: GameConfig) -> Minefield {
+        self.generate_reporting(config).0
+    }
+
+    fn generate_from(mut self, params: GenerationParams) -> Minefield {
+        if let Some(max_attempts) = params.max_attempts {
+            self.max_attempts = max_attempts;
+        }
+        self.generate(params.config)
+    }
+
+    /// Reports [`GenerationOutcome::ExhaustedAttempts`] when no board met the requirement, otherwise what the inner
+    /// generator reported for the board
+    fn generate_reporting(self, config: GameConfig) -> (Minefield, GenerationOutcome) {
+        let mut rng = SplitMix64::new(self.inner.seed());
+        let start = self.inner.start();
+        let mut best: Option<(usize, Minefield)> = None;
+        for _ in 0..self.max_attempts.max(1) {
+            let (minefield, outcome) = self.inner.generate_reporting_with(config, &mut rng);
+            let shortfall = self.requirement.shortfall(&minefield, start);
+            if shortfall == 0 {
+                return (minefield, outcome);
+            }
+            if best
+                .as_ref()
+                .is_none_or(|(best_shortfall, _)| shortfall < *best_shortfall)
+            {
+                best = Some((shortfall, minefield));
+            }
+        }
+        let (_, minefield) = best.expect("at least one attempt is made");
+        self.requirement
+            .warn_exhausted(&minefield, start, self.max_attempts);
+        (minefield, GenerationOutcome::ExhaustedAttempts)
+    }
+}
@@ -0,0 +1,81 @@
+use rand::RngCore;
+
+/// SplitMix64, a tiny RNG that is fully specified here, unlike `rand`'s `SmallRng` whose algorithm may change between
+/// versions. With it and [`below`] a seed gives the same minefield no matter which `rand` is used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Uniform value in `0..bound` using Lemire's multiply-and-reject method on [`RngCore::next_u64`], written out here
+/// instead of `gen_range` so the sampling can't change with `rand` either
+pub(crate) fn below(rng: &mut impl RngCore, bound: u64) -> u64 {
+    assert!(bound > 0, "empty range");
+    let mut product = u128::from(rng.next_u64()) * u128::from(bound);
+    if (product as u64) < bound {
+        let threshold = bound.wrapping_neg() % bound;
+        while (product as u64) < threshold {
+            product = u128::from(rng.next_u64()) * u128::from(bound);
+        }
+    }
+    (product >> 64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitmix_reference_output() {
+        // from the reference implementation seeded with 1234567
+        let mut rng = SplitMix64::new(1234567);
+        for expected in [
+            6457827717110365317,
+            3203168211198807973,
+            9817491932198370423,
+            4593380528125082431,
+        ] {
+            assert_eq!(rng.next_u64(), expected);
+        }
+    }
+
+    #[test]
+    fn below_stays_in_range() {
+        let mut rng = SplitMix64::new(0);
+        for bound in [1, 2, 3, 81, 1000] {
+            assert!((0..100).all(|_| below(&mut rng, bound) < bound));
+        }
+    }
+}
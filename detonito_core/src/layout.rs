@@ -0,0 +1,116 @@
+//! Const helpers behind [`minefield!`](crate::minefield), layouts are rows of `*` (mine) and `.` (safe), invalid
+//! layouts panic, which is a compile error when evaluated in a const
+
+use crate::types::*;
+
+/// Size of a layout, every row must have the same non-zero length
+pub const fn layout_size(rows: &[&str]) -> Ix2 {
+    assert!(!rows.is_empty(), "layout has no rows");
+    assert!(rows.len() <= Ix::MAX as usize, "layout has too many rows");
+    let size_x = rows[0].len();
+    assert!(size_x > 0, "layout rows are empty");
+    assert!(size_x <= Ix::MAX as usize, "layout rows are too long");
+    let mut y = 0;
+    while y < rows.len() {
+        assert!(
+            rows[y].len() == size_x,
+            "layout rows have different lengths"
+        );
+        let row = rows[y].as_bytes();
+        let mut x = 0;
+        while x < row.len() {
+            assert!(
+                row[x] == b'*' || row[x] == b'.',
+                "layout can only contain `*` and `.`"
+            );
+            x += 1;
+        }
+        y += 1;
+    }
+    (size_x as Ix, rows.len() as Ix)
+}
+
+/// How many `*` there are in a layout
+pub const fn layout_mine_count(rows: &[&str]) -> usize {
+    let mut count = 0;
+    let mut y = 0;
+    while y < rows.len() {
+        let row = rows[y].as_bytes();
+        let mut x = 0;
+        while x < row.len() {
+            if row[x] == b'*' {
+                count += 1;
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    count
+}
+
+/// Coordinates of every `*` in row-major order, `N` must be [`layout_mine_count`]
+pub const fn layout_mines<const N: usize>(rows: &[&str]) -> [Ix2; N] {
+    let (size_x, size_y) = layout_size(rows);
+    assert!(
+        N < size_x as usize * size_y as usize,
+        "layout has no safe tile"
+    );
+    let mut mines = [(0, 0); N];
+    let mut i = 0;
+    let mut y = 0;
+    while y < size_y {
+        let row = rows[y as usize].as_bytes();
+        let mut x = 0;
+        while x < size_x {
+            if row[x as usize] == b'*' {
+                assert!(i < N, "layout has more mines than expected");
+                mines[i] = (x, y);
+                i += 1;
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    assert!(i == N, "layout has fewer mines than expected");
+    mines
+}
+
+/// Build a [`Minefield`](crate::Minefield) from `*`/`.` rows checked at compile time, only the mine coordinates are
+/// copied at runtime
+///
+/// ```
+/// let minefield = detonito_core::minefield!(
+///     "*..",
+///     ".*.",
+///     "...",
+/// );
+/// assert_eq!(minefield.size(), (3, 3));
+/// assert_eq!(minefield.game_config().mines, 2);
+/// ```
+#[macro_export]
+macro_rules! minefield {
+    ($($row:literal),+ $(,)?) => {{
+        const ROWS: &[&str] = &[$($row),+];
+        const SIZE: $crate::Ix2 = $crate::layout_size(ROWS);
+        const MINES: [$crate::Ix2; $crate::layout_mine_count(ROWS)] = $crate::layout_mines(ROWS);
+        $crate::Minefield::from_mine_coords(SIZE, &MINES).unwrap()
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Minefield;
+
+    #[test]
+    fn macro_matches_runtime_layout() {
+        let minefield = crate::minefield!("*..", ".*.", "...");
+        assert_eq!(
+            minefield,
+            Minefield::from_binary_rows(&["100", "010", "000"]).unwrap()
+        );
+        assert_eq!(layout_mines::<2>(&["*..", ".*.", "..."]), [(0, 0), (1, 1)]);
+        assert_eq!(crate::minefield!("...").game_config().mines, 0);
+        assert!(Minefield::from_mine_coords((2, 2), &[(2, 0)]).is_err());
+    }
+}
@@ -2,19 +2,39 @@
 
 extern crate alloc;
 
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
 use chrono::prelude::*;
 use ndarray::Array2;
 use serde::{Deserialize, Serialize};
 use core::num::Saturating;
 use core::ops::{BitOr, Index, IndexMut};
 
+pub use action::*;
+pub use deferred::*;
+pub use difficulty::*;
 pub use error::*;
 pub use generator::*;
+pub use layout::*;
+pub use multi::*;
+pub use replay::*;
+pub use rules::*;
+pub use simulation::*;
+pub use solver::*;
 pub use tile::*;
 pub use types::*;
 
+mod action;
+mod deferred;
+mod difficulty;
 mod error;
 mod generator;
+mod layout;
+mod multi;
+mod replay;
+mod rules;
+mod simulation;
+mod solver;
 mod tile;
 mod types;
 
@@ -25,20 +45,81 @@ pub struct GameConfig {
 }
 
 impl GameConfig {
+    /// Cap on the area of a board, each axis can go up to [`Ix::MAX`] but not both at once, a 99×99 board is already
+    /// a lot of tiles to render
+    pub const MAX_TOTAL_TILES: Ax = 99 * 99;
+
+    pub const BEGINNER: Self = Self::new_unchecked((9, 9), 10);
+    pub const INTERMEDIATE: Self = Self::new_unchecked((16, 16), 40);
+    pub const EXPERT: Self = Self::new_unchecked((30, 16), 99);
+    pub const EVIL: Self = Self::new_unchecked((30, 20), 130);
+
     pub const fn new_unchecked(size: Ix2, mines: Ax) -> Self {
         Self { size, mines }
     }
 
+    /// Like [`GameConfig::new`] but rejects instead of clamping, the area is limited to [`GameConfig::MAX_TOTAL_TILES`]
+    pub fn try_new(size: Ix2, mines: Ax) -> Result<Self> {
+        let config = Self::new_unchecked(size, mines);
+        if size.0 == 0 || size.1 == 0 {
+            return Err(GameError::InvalidBoardShape);
+        }
+        if config.total_tiles() > Self::MAX_TOTAL_TILES {
+            return Err(GameError::InvalidCoords);
+        }
+        if mines > config.max_mines() {
+            return Err(GameError::TooManyMines);
+        }
+        Ok(config)
+    }
+
+    /// Whether [`GameConfig::try_new`] accepts this config, a deserialized one can leave no tile without a mine
+    pub fn is_valid(&self) -> bool {
+        Self::try_new(self.size, self.mines).is_ok()
+    }
+
     pub fn new((size_x, size_y): Ix2, mines: Ax) -> Self {
         let size_x = size_x.clamp(1, Ix::MAX);
         let size_y = size_y.clamp(1, Ix::MAX);
-        let mines = mines.clamp(1, mult(size_x, size_y));
+        let max_mines = mult(size_x, size_y) - 1;
+        let mines = mines.clamp(max_mines.min(1), max_mines);
         Self::new_unchecked((size_x, size_y), mines)
     }
 
     pub const fn total_tiles(&self) -> Ax {
         mult(self.size.0, self.size.1)
     }
+
+    /// Most mines that still leave a safe tile, a board without one can't be won
+    pub const fn max_mines(&self) -> Ax {
+        self.total_tiles().saturating_sub(1)
+    }
+
+    /// Fraction of tiles that are mines, about 0.12 for beginner and 0.21 for expert
+    pub fn mine_density(&self) -> f32 {
+        f32::from(self.mines) / f32::from(self.total_tiles().max(1))
+    }
+
+    /// Config with the mine count nearest to `density` for this size, at least one mine and one safe tile when the
+    /// board has room for both. The size is clamped like [`GameConfig::new`].
+    pub fn from_density(size: Ix2, density: f32) -> Self {
+        let size = Self::new(size, 1).size;
+        let total_tiles = mult(size.0, size.1);
+        // the cast saturates, NaN and negative densities end up as 0
+        let mines = (density * f32::from(total_tiles) + 0.5) as Ax;
+        Self::new_unchecked(size, mines.clamp(1, (total_tiles - 1).max(1)))
+    }
+
+    /// Stable name of the preset this config matches exactly, e.g. `"expert"`, `None` for custom configs
+    pub fn preset_name(&self) -> Option<&'static str> {
+        match *self {
+            Self::BEGINNER => Some("beginner"),
+            Self::INTERMEDIATE => Some("intermediate"),
+            Self::EXPERT => Some("expert"),
+            Self::EVIL => Some("evil"),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -48,6 +129,249 @@ pub struct Minefield {
 }
 
 impl Minefield {
+    fn from_mines(mines: Array2<bool>) -> Result<Self> {
+        let count = mines.iter().filter(|&&mine| mine).count();
+        let count = count.try_into().map_err(|_| GameError::InvalidBoardShape)?;
+        let minefield = Self { mines, count };
+        minefield.validate()?;
+        Ok(minefield)
+    }
+
+    /// Parse rows of `0` (safe) and `1` (mine), as exported by some clones, whitespace is ignored and every row must
+    /// have the same length
+    pub fn from_binary_rows(rows: &[&str]) -> Result<Self> {
+        let rows: Vec<Vec<bool>> = rows
+            .iter()
+            .map(|row| {
+                row.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .map(|c| match c {
+                        '0' => Ok(false),
+                        '1' => Ok(true),
+                        _ => Err(GameError::InvalidLayoutCharacter),
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .filter(|row| !matches!(row, Ok(row) if row.is_empty()))
+            .collect::<Result<_>>()?;
+        let size_y = rows.len();
+        let size_x = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != size_x) {
+            return Err(GameError::InvalidBoardShape);
+        }
+        Self::from_mines(Array2::from_shape_fn((size_x, size_y), |(x, y)| rows[y][x]))
+    }
+
+    /// Place mines at the given coordinates, duplicates are ignored, see [`minefield!`] for fixed layouts
+    pub fn from_mine_coords(size: Ix2, coords: &[Ix2]) -> Result<Self> {
+        let mut mines = Array2::default(size.convert());
+        for &(x, y) in coords {
+            if x >= size.0 || y >= size.1 {
+                return Err(GameError::InvalidCoords);
+            }
+            mines[(x, y).convert()] = true;
+        }
+        Self::from_mines(mines)
+    }
+
+    /// Safe tiles with no adjacent mines, any of them is a first move that opens a region
+    pub fn zero_cells(&self) -> Vec<Ix2> {
+        iter_coords(self.size())
+            .filter(|&coords| !self[coords] && self.get_count(coords) == 0)
+            .collect()
+    }
+
+    /// Every tile with whether it's a mine, in row-major order
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Ix2, bool)> + '_ {
+        iter_coords(self.size()).map(|coords| (coords, self[coords]))
+    }
+
+    /// The whole mine layout indexed `[x, y]`, for bulk read-only work like rendering a solved board. This is the
+    /// solution, don't hand it to anything the player can see before the game ends.
+    ///
+    /// ```
+    /// use detonito_core::*;
+    ///
+    /// let minefield = Minefield::from_mine_coords((3, 2), &[(0, 0), (2, 1)]).unwrap();
+    /// let mask = minefield.mine_mask();
+    /// assert!(mask[[2, 1]]);
+    /// assert_eq!(mask.iter().filter(|&&mine| mine).count(), minefield.game_config().mines.into());
+    /// ```
+    pub fn mine_mask(&self) -> &Array2<bool> {
+        &self.mines
+    }
+
+    pub fn safe_cells(&self) -> Vec<Ix2> {
+        iter_coords(self.size())
+            .filter(|&coords| !self[coords])
+            .collect()
+    }
+
+    /// Fraction of tiles within Chebyshev distance `radius` of `coords` (clipped to the board) that are mines
+    pub fn local_density(&self, (x, y): Ix2, radius: u8) -> f32 {
+        let (size_x, size_y) = self.size();
+        let xs = x.saturating_sub(radius)..=x.saturating_add(radius).min(size_x - 1);
+        let ys = y.saturating_sub(radius)..=y.saturating_add(radius).min(size_y - 1);
+        let mut tiles = 0u32;
+        let mut mines = 0u32;
+        for wy in ys {
+            for wx in xs.clone() {
+                tiles += 1;
+                mines += u32::from(self[(wx, wy)]);
+            }
+        }
+        mines as f32 / tiles as f32
+    }
+
+    /// Highest [`Minefield::local_density`] over the whole board, useful to reject clumpy minefields
+    pub fn max_local_density(&self, radius: u8) -> f32 {
+        iter_coords(self.size())
+            .map(|coords| self.local_density(coords, radius))
+            .fold(0.0, f32::max)
+    }
+
+    /// Every zero region as its first zero in row-major order with how many tiles opening it reveals, plus which tiles
+    /// are revealed by some zero region
+    fn openings(&self) -> (Vec<(Ix2, usize)>, Array2<bool>) {
+        let mut region_of: Array2<Option<usize>> = Array2::default(self.mines.dim());
+        let mut openings = Vec::new();
+        for coords in iter_coords(self.size()) {
+            if self[coords] || self.get_count(coords) != 0 || region_of[coords.convert()].is_some()
+            {
+                continue;
+            }
+            let size = self.mark_region(coords, &mut region_of, openings.len());
+            openings.push((coords, size));
+        }
+        (openings, region_of.map(Option::is_some))
+    }
+
+    /// Mark the tiles opened by flooding from the zero at `start` as part of `region`, returns how many there are
+    fn mark_region(
+        &self,
+        start: Ix2,
+        region_of: &mut Array2<Option<usize>>,
+        region: usize,
+    ) -> usize {
+        let region = Some(region);
+        region_of[start.convert()] = region;
+        let mut size = 1;
+        let mut to_visit = alloc::vec![start];
+        while let Some(visit_coords) = to_visit.pop() {
+            // the neighbors of a zero are never mines
+            if self.get_count(visit_coords) != 0 {
+                continue;
+            }
+            for pos in self.mines.iter_adjacent(visit_coords) {
+                // counts next to several regions are revealed by each of them
+                if region_of[pos.convert()] != region {
+                    region_of[pos.convert()] = region;
+                    size += 1;
+                    to_visit.push(pos);
+                }
+            }
+        }
+        size
+    }
+
+    /// How many tiles opening `coords` first reveals with flood fill: 0 for a mine, 1 for a count and the whole
+    /// opening for a zero, see [`Minefield::flood_region`]
+    pub fn opening_size(&self, coords: Ix2) -> usize {
+        self.flood_region(coords).len()
+    }
+
+    /// Tiles that opening `coords` first reveals with flood fill on a fresh board, in row-major order: none for a mine,
+    /// only `coords` for a count and for a zero its whole zero region plus the counts around it. Unlike
+    /// [`Game::open`] this only looks at the minefield.
+    pub fn flood_region(&self, coords: Ix2) -> Vec<Ix2> {
+        if self[coords] {
+            return Vec::new();
+        }
+        if self.get_count(coords) != 0 {
+            return alloc::vec![coords];
+        }
+        let mut region_of = Array2::default(self.mines.dim());
+        self.mark_region(coords, &mut region_of, 0);
+        iter_coords(self.size())
+            .filter(|&pos| region_of[pos.convert()].is_some())
+            .collect()
+    }
+
+    /// Grayscale preview of where the mines are, downsampled so neither side is longer than `max_dim` pixels. Each
+    /// pixel covers a square block of tiles (smaller at the right and bottom edges) and is its mine density scaled to
+    /// 0-255, indexed like the minefield.
+    ///
+    /// ```
+    /// use detonito_core::*;
+    ///
+    /// let minefield = Minefield::from_mine_coords((4, 4), &[(0, 0), (1, 0), (0, 1), (1, 1)]).unwrap();
+    /// let thumbnail = minefield.to_thumbnail(2);
+    /// assert_eq!(thumbnail.shape(), [2, 2]);
+    /// assert_eq!(thumbnail[[0, 0]], 255);
+    /// assert_eq!(thumbnail[[1, 1]], 0);
+    /// ```
+    pub fn to_thumbnail(&self, max_dim: usize) -> Array2<u8> {
+        let (dim_x, dim_y) = self.mines.dim();
+        let block = dim_x.max(dim_y).div_ceil(max_dim.max(1)).max(1);
+        let mut mines = Array2::<usize>::zeros((dim_x.div_ceil(block), dim_y.div_ceil(block)));
+        for ((x, y), &mine) in self.mines.indexed_iter() {
+            mines[[x / block, y / block]] += usize::from(mine);
+        }
+        Array2::from_shape_fn(mines.dim(), |(x, y)| {
+            let tiles =
+                (dim_x.min((x + 1) * block) - x * block) * (dim_y.min((y + 1) * block) - y * block);
+            // rounded to the nearest
+            ((mines[[x, y]] * 255 + tiles / 2) / tiles) as u8
+        })
+    }
+
+    /// Bechtel's Board Benchmark Value (3BV): the fewest clicks that clear the board without flags or chords, each zero
+    /// region counts once plus each safe tile that no zero region opens
+    pub fn bbbv(&self) -> Ax {
+        let (openings, opened) = self.openings();
+        let isolated = iter_coords(self.size())
+            .filter(|&coords| !self[coords] && !opened[coords.convert()])
+            .count();
+        (openings.len() + isolated) as Ax
+    }
+
+    /// The zero that opens the most tiles, the first one in row-major order on ties, with how many tiles it opens,
+    /// `((0, 0), 0)` when there are no zeros
+    pub fn best_opening(&self) -> (Ix2, usize) {
+        let (openings, _) = self.openings();
+        openings.into_iter().fold(
+            ((0, 0), 0),
+            |best, opening| if opening.1 > best.1 { opening } else { best },
+        )
+    }
+
+    /// Connected groups of safe tiles, diagonals included, each sorted in row-major order and the groups ordered by
+    /// their first tile
+    pub fn safe_regions(&self) -> Vec<Vec<Ix2>> {
+        let mut visited = Array2::from_elem(self.mines.dim(), false);
+        let mut regions = Vec::new();
+        for coords in iter_coords(self.size()) {
+            if self[coords] || visited[coords.convert()] {
+                continue;
+            }
+            visited[coords.convert()] = true;
+            let mut region = Vec::new();
+            let mut to_visit = alloc::vec![coords];
+            while let Some(visit_coords) = to_visit.pop() {
+                region.push(visit_coords);
+                for pos in self.mines.iter_adjacent(visit_coords) {
+                    if !self[pos] && !visited[pos.convert()] {
+                        visited[pos.convert()] = true;
+                        to_visit.push(pos);
+                    }
+                }
+            }
+            region.sort_unstable_by_key(|&(x, y)| (y, x));
+            regions.push(region);
+        }
+        regions
+    }
+
     pub fn game_config(&self) -> GameConfig {
         GameConfig {
             size: self.size(),
@@ -55,6 +379,32 @@ impl Minefield {
         }
     }
 
+    /// Check that the board shape fits in [`Ix2`] and that the stored mine count matches the actual mines, this catches
+    /// corrupted minefields (e.g. deserialized ones) before they reach a game.
+    ///
+    /// A board with no safe tile can't be won and is rejected, a board with no mines is fine: the first open floods it
+    /// and wins instantly.
+    pub fn validate(&self) -> Result<()> {
+        let (dim_x, dim_y) = self.mines.dim();
+        if dim_x == 0 || dim_y == 0 || dim_x > Ix::MAX.into() || dim_y > Ix::MAX.into() {
+            return Err(GameError::InvalidBoardShape);
+        }
+        let actual = self.mines.iter().filter(|&&mine| mine).count();
+        if actual != usize::from(self.count) {
+            return Err(GameError::MineCountMismatch);
+        }
+        if self.count >= self.total_tiles() {
+            return Err(GameError::TooManyMines);
+        }
+        Ok(())
+    }
+
+    /// Neighbors by fixed position, top-left to bottom-right row by row skipping the center (so 1, 3, 4 and 6 are the
+    /// orthogonal ones), `None` when out of bounds, plus how many are in bounds
+    pub fn neighbor_array(&self, coords: Ix2) -> ([Option<Ix2>; 8], u8) {
+        adjacent_array(coords, self.size())
+    }
+
     pub fn validate_coords(&self, coords: Ix2) -> Result<Ix2> {
         let size = self.size();
         if coords.0 < size.0 && coords.1 < size.1 {
@@ -65,8 +415,7 @@ impl Minefield {
     }
 
     pub fn size(&self) -> Ix2 {
-        let dim = self.mines.dim();
-        (dim.0.try_into().unwrap(), dim.1.try_into().unwrap())
+        shape_of(&self.mines)
     }
 
     pub fn safe_count(&self) -> Ax {
@@ -74,7 +423,7 @@ impl Minefield {
     }
 
     pub fn total_tiles(&self) -> Ax {
-        self.mines.len().try_into().unwrap()
+        self.mines.len().try_into().unwrap_or(Ax::MAX)
     }
 
     pub fn get_count(&self, coords: Ix2) -> u8 {
@@ -85,6 +434,37 @@ impl Minefield {
             .try_into()
             .unwrap()
     }
+
+    /// How many mines are up to `radius` tiles away in both axes, so a radius of 2 counts the 5×5 square around the
+    /// tile, a radius of 1 is the same as [`Minefield::get_count`]. Saturates at [`u8::MAX`].
+    pub fn region_mine_count(&self, coords: Ix2, radius: u8) -> u8 {
+        iter_region(coords, radius, self.size())
+            .filter(|&pos| self[pos])
+            .count()
+            .try_into()
+            .unwrap_or(u8::MAX)
+    }
+
+    /// How many tiles are adjacent to this one, 3 in a corner, 5 on an edge and 8 elsewhere on boards of at least 2×2
+    pub fn neighbor_count(&self, coords: Ix2) -> u8 {
+        let size = self.size();
+        let span = |pos: Ix, len: Ix| 1 + u8::from(pos > 0) + u8::from(pos + 1 < len);
+        span(coords.0, size.0) * span(coords.1, size.1) - 1
+    }
+
+    /// Whether the tile is at an end of both axes
+    pub fn is_corner(&self, coords: Ix2) -> bool {
+        let size = self.size();
+        let at_end = |pos: Ix, len: Ix| pos == 0 || pos + 1 == len;
+        at_end(coords.0, size.0) && at_end(coords.1, size.1)
+    }
+
+    /// Whether the tile is on the border of the board without being a corner
+    pub fn is_edge(&self, coords: Ix2) -> bool {
+        let size = self.size();
+        let at_end = |pos: Ix, len: Ix| pos == 0 || pos + 1 == len;
+        at_end(coords.0, size.0) != at_end(coords.1, size.1)
+    }
 }
 
 impl Index<Ix2> for Minefield {
@@ -102,13 +482,22 @@ impl IndexMut<Ix2> for Minefield {
 }
 
 /// Outcome of opening a tile
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FlagOutcome {
     NoChange,
     MarkChanged,
 }
 
 impl FlagOutcome {
+    /// Stable name, same as the serialized form
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::NoChange => "no_change",
+            Self::MarkChanged => "mark_changed",
+        }
+    }
+
     /// Whether this outcome could have caused an update to the game
     pub const fn has_update(self) -> bool {
         match self {
@@ -119,7 +508,8 @@ impl FlagOutcome {
 }
 
 /// Outcome of opening a tile
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum OpenOutcome {
     NoChange,
     Safe,
@@ -128,6 +518,17 @@ pub enum OpenOutcome {
 }
 
 impl OpenOutcome {
+    /// Stable name, same as the serialized form
+    pub const fn as_str(self) -> &'static str {
+        use OpenOutcome::*;
+        match self {
+            NoChange => "no_change",
+            Safe => "safe",
+            Explode => "explode",
+            Win => "win",
+        }
+    }
+
     /// Whether this outcome could have caused an update to the game
     pub const fn has_update(self) -> bool {
         use OpenOutcome::*;
@@ -163,29 +564,65 @@ impl BitOr for OpenOutcome {
     }
 }
 
+/// How [`Game::auto_play`] stopped
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoPlayResult {
+    Won,
+    /// Only possible when wrong flags misled the solver
+    Lost,
+    /// The solver proves nothing new, the next move is a guess
+    Stuck,
+    StepLimit,
+}
+
 /// Valid transitions:
 /// - NotStarted -> InstantWin
+/// - NotStarted -> Win, with [`Game::set_instant_win_as_win`]
 /// - NotStarted -> InstantLoss
 /// - NotStarted -> InProgress
 /// - InProgress -> Win
 /// - InProgress -> Loss
-#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+///
+/// Serialized as the snake_case names from [`GameState::as_str`], the old variant names are still accepted.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GameState {
     /// Initial state
+    #[default]
+    #[serde(alias = "NotStarted")]
     NotStarted,
     /// Game started
+    #[serde(alias = "InProgress")]
     InProgress,
     /// Game ended and player won
+    #[serde(alias = "Win")]
     Win,
     /// Game ended and player lost
+    #[serde(alias = "Lose")]
     Lose,
     /// Game ended and player won on the first move
+    #[serde(alias = "InstantWin")]
     InstantWin,
     /// Game ended and player lost on the first move
+    #[serde(alias = "InstantLoss")]
     InstantLoss,
 }
 
 impl GameState {
+    /// Stable name, same as the serialized form
+    pub const fn as_str(self) -> &'static str {
+        use GameState::*;
+        match self {
+            NotStarted => "not_started",
+            InProgress => "in_progress",
+            Win => "win",
+            Lose => "lose",
+            InstantWin => "instant_win",
+            InstantLoss => "instant_loss",
+        }
+    }
+
     /// Indicates the game has not started yet
     pub const fn is_initial(self) -> bool {
         use GameState::*;
@@ -213,9 +650,66 @@ impl GameState {
     }
 }
 
-impl Default for GameState {
-    fn default() -> Self {
-        Self::NotStarted
+/// What the neighbors of a tile look like, every in-bounds neighbor is counted exactly once
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct NeighborSummary {
+    /// Flagged or exploded, the neighbors chording treats as mines
+    pub flagged: u8,
+    pub questioned: u8,
+    /// Every other tile that isn't open, mines and wrong flags only show up here once the game ended
+    pub closed: u8,
+    pub open: u8,
+}
+
+impl NeighborSummary {
+    /// Neighbors that aren't open
+    pub fn unopened(&self) -> u8 {
+        self.flagged + self.questioned + self.closed
+    }
+
+    pub fn total(&self) -> u8 {
+        self.unopened() + self.open
+    }
+
+    /// Whether an open tile with this count can be chorded: the flags match it and no neighbor has a question mark
+    pub fn allows_chord(&self, count: u8) -> bool {
+        self.flagged == count && self.questioned == 0
+    }
+}
+
+/// Summary of a game for an end of game screen or stats, see [`Game::report`]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameReport {
+    pub state: GameState,
+    pub config: GameConfig,
+    /// Same as [`Game::elapsed_secs`]
+    pub elapsed_secs: u32,
+    /// Safe tiles opened
+    pub opened: Ax,
+    /// Flags placed, including wrong ones
+    pub flags: Ax,
+    /// Mines opened in lenient mode
+    pub mistakes: Ax,
+    /// [`Minefield::bbbv`] of the whole board
+    pub bbbv: Ax,
+}
+
+impl GameReport {
+    /// Arcade score of a won game, 0 otherwise:
+    ///
+    /// `10_000 × mines² / tiles / (elapsed_secs + 1)`
+    ///
+    /// `mines² / tiles` is the mine count weighted by the density, so bigger and denser boards are worth more, about
+    /// 1 for beginner, 20 for expert and 28 for evil. Faster wins never score less, the result saturates at
+    /// [`u32::MAX`].
+    pub fn score(&self) -> u32 {
+        if !matches!(self.state, GameState::Win | GameState::InstantWin) {
+            return 0;
+        }
+        let mines = u64::from(self.config.mines);
+        let tiles = u64::from(self.config.total_tiles()).max(1);
+        let score = 10_000 * mines * mines / tiles / (u64::from(self.elapsed_secs) + 1);
+        score.try_into().unwrap_or(u32::MAX)
     }
 }
 
@@ -229,13 +723,41 @@ pub struct Game {
     state: GameState,
     started_at: Option<DateTime<Utc>>,
     ended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    mistakes: Ax,
+    /// Mines opened by the losing move
+    #[serde(default)]
+    exploded_mines: Vec<Ix2>,
+    /// Closed tiles next to the last ring opened by [`Game::open_step`], opened by the next call
+    #[serde(default)]
+    flood_frontier: BTreeSet<Ix2>,
+    /// Stored inline so games saved before the rules were grouped still load
+    #[serde(flatten)]
+    rules: RulesConfig,
 }
 
 impl Game {
     // Initialize the grid
     pub fn new(minefield: Minefield) -> Game {
+        Self::with_rules(minefield, RulesConfig::default())
+    }
+
+    /// Initialize a game played with a rule variant, the clue radius is clamped like [`Game::set_clue_radius`]
+    ///
+    /// ```
+    /// use detonito_core::*;
+    /// use chrono::DateTime;
+    ///
+    /// let minefield = Minefield::from_mine_coords((4, 4), &[(3, 3)]).unwrap();
+    /// let rules = RulesConfig { flood_fill: false, ..Default::default() };
+    /// let mut game = Game::with_rules(minefield, rules);
+    /// game.open((0, 0), DateTime::UNIX_EPOCH).unwrap();
+    /// assert_eq!(game.cur_state(), GameState::InProgress);
+    /// assert!(!game.is_open_at((1, 0)));
+    /// ```
+    pub fn with_rules(minefield: Minefield, rules: RulesConfig) -> Game {
         let size = minefield.size();
-        Self {
+        let mut game = Self {
             minefield,
             grid: Array2::default(size.convert()),
             open_count: Saturating(0),
@@ -243,7 +765,281 @@ impl Game {
             state: Default::default(),
             started_at: None,
             ended_at: None,
+            mistakes: 0,
+            exploded_mines: Vec::new(),
+            flood_frontier: BTreeSet::new(),
+            rules: RulesConfig::default(),
+        };
+        game.set_rules(rules);
+        game
+    }
+
+    /// Initialize a game with some tiles already flagged, e.g. a puzzle handing out hints, duplicates are ignored.
+    /// Flags may be wrong like a player's unless `strict` requires them to be on mines.
+    pub fn with_flags(minefield: Minefield, flags: &[Ix2], strict: bool) -> Result<Game> {
+        let mut game = Self::new(minefield);
+        for &coords in flags {
+            let coords = game.minefield.validate_coords(coords)?;
+            if strict && !game.minefield[coords] {
+                return Err(GameError::FlagOnSafeTile);
+            }
+            if game.grid[coords.convert()] == AnyTile::Closed {
+                game.grid[coords.convert()] = AnyTile::Flag;
+                game.flag_count += 1;
+            }
+        }
+        Ok(game)
+    }
+
+    /// Initialize a practice game, see [`Game::set_lenient`]
+    pub fn new_lenient(minefield: Minefield) -> Game {
+        let mut game = Self::new(minefield);
+        game.rules.lenient = true;
+        game
+    }
+
+    /// Reset to the initial state, keeping the same minefield and rules
+    pub fn restart(&mut self) {
+        self.grid.fill(AnyTile::Closed);
+        self.open_count = Saturating(0);
+        self.flag_count = Saturating(0);
+        self.state = Default::default();
+        self.started_at = None;
+        self.ended_at = None;
+        self.mistakes = 0;
+        self.exploded_mines.clear();
+        self.flood_frontier.clear();
+    }
+
+    /// Reset to the initial state with a freshly generated minefield of the same config, `make_generator` is called with
+    /// `first_move` so the generator's start tile policy applies to the move the player makes next
+    pub fn restart_regenerated<G: MinefieldGenerator>(
+        &mut self,
+        make_generator: impl FnOnce(Ix2) -> G,
+        first_move: Ix2,
+    ) -> Result<()> {
+        let first_move = self.minefield.validate_coords(first_move)?;
+        let config = self.minefield.game_config();
+        self.minefield = make_generator(first_move).generate(config);
+        self.restart();
+        Ok(())
+    }
+
+    /// Check the minefield is consistent, the grid matches its shape, the timestamps match the state and the counters
+    /// match the grid, a saved game failing this would report a wrong elapsed time, first move or win
+    pub fn validate(&self) -> Result<()> {
+        use GameState::*;
+
+        self.minefield.validate()?;
+        if self.grid.dim() != self.minefield.mines.dim() {
+            return Err(GameError::InvalidBoardShape);
+        }
+        // with a radius of 0 every clue is a zero, which would flood its neighbors without checking for mines
+        if !(1..=Self::MAX_CLUE_RADIUS).contains(&self.rules.clue_radius) {
+            return Err(GameError::InvalidClueRadius);
+        }
+        let timestamps_match = match (self.state, self.started_at, self.ended_at) {
+            (NotStarted, None, None) => true,
+            (InProgress, Some(_), None) => true,
+            (Win | Lose | InstantWin | InstantLoss, Some(started_at), Some(ended_at)) => {
+                started_at <= ended_at
+            }
+            _ => false,
+        };
+        let opened = self
+            .grid
+            .iter()
+            .filter(|tile| matches!(tile, AnyTile::Open(_)))
+            .count();
+        let flagged = self
+            .grid
+            .iter()
+            .filter(|&&tile| tile == AnyTile::Flag)
+            .count();
+        // wrong flags are no longer flags once the game is lost
+        let flags_match = self.ended() || flagged == usize::from(self.flag_count.0);
+        if !timestamps_match || opened != usize::from(self.open_count.0) || !flags_match {
+            return Err(GameError::InconsistentProgress);
+        }
+        Ok(())
+    }
+
+    /// Recount the open tiles and flags from the grid, a hand edited or corrupted save can have counts that drifted
+    /// from it and make win detection fire too early or never. Returns whether any count was wrong.
+    pub fn recompute_counts(&mut self) -> bool {
+        let count = |matches: fn(&AnyTile) -> bool| {
+            let count = self.grid.iter().filter(|tile| matches(tile)).count();
+            Saturating(Ax::try_from(count).unwrap_or(Ax::MAX))
+        };
+        let open_count = count(|tile| matches!(tile, AnyTile::Open(_)));
+        // wrong flags are still counted once the game is lost
+        let flag_count = count(|tile| matches!(tile, AnyTile::Flag | AnyTile::IncorrectFlag));
+        let changed = open_count != self.open_count || flag_count != self.flag_count;
+        self.open_count = open_count;
+        self.flag_count = flag_count;
+        changed
+    }
+
+    pub fn rules(&self) -> &RulesConfig {
+        &self.rules
+    }
+
+    /// Replace every rule at once, the clue radius is clamped like [`Game::set_clue_radius`]. Like the single rule
+    /// setters it's meant to be used before the first move.
+    pub fn set_rules(&mut self, rules: RulesConfig) {
+        let clue_radius = rules.clue_radius;
+        self.rules = rules;
+        self.set_clue_radius(clue_radius);
+    }
+
+    pub fn is_lenient(&self) -> bool {
+        self.rules.lenient
+    }
+
+    /// In lenient mode opening a mine marks it as exploded and counts a mistake, the game goes on and is still won by
+    /// opening every safe tile
+    pub fn set_lenient(&mut self, lenient: bool) {
+        self.rules.lenient = lenient;
+    }
+
+    pub fn is_flood_fill(&self) -> bool {
+        self.rules.flood_fill
+    }
+
+    /// Without flood fill opening a zero only opens that tile, the game is still won by opening every safe tile
+    pub fn set_flood_fill(&mut self, flood_fill: bool) {
+        self.rules.flood_fill = flood_fill;
+    }
+
+    pub fn is_auto_flag_on_win(&self) -> bool {
+        self.rules.auto_flag_on_win
+    }
+
+    /// Without it the mines left unflagged stay closed after a win, either way the mines are revealed after a loss
+    pub fn set_auto_flag_on_win(&mut self, auto_flag_on_win: bool) {
+        self.rules.auto_flag_on_win = auto_flag_on_win;
+    }
+
+    pub fn chord_ignores_questions(&self) -> bool {
+        self.rules.chord_ignores_questions
+    }
+
+    /// By default a question mark next to an open tile blocks chording it, with this set chording opens question
+    /// marked neighbors too, and they explode like closed ones if they hide a mine
+    pub fn set_chord_ignores_questions(&mut self, chord_ignores_questions: bool) {
+        self.rules.chord_ignores_questions = chord_ignores_questions;
+    }
+
+    pub fn max_flags_enforced(&self) -> bool {
+        self.rules.max_flags_enforced
+    }
+
+    /// Careful mode, [`Game::flag`], [`Game::flag_question`] and [`Game::chord_flag`] do nothing when the new flags
+    /// would push [`Game::mines_left`] below 0, flags already placed are kept
+    pub fn set_max_flags_enforced(&mut self, max_flags_enforced: bool) {
+        self.rules.max_flags_enforced = max_flags_enforced;
+    }
+
+    pub fn instant_win_as_win(&self) -> bool {
+        self.rules.instant_win_as_win
+    }
+
+    /// Boards where the first opening clears every safe tile are won like any other game, stats that skip
+    /// [`GameState::InstantWin`] count them, an instant loss is still [`GameState::InstantLoss`]
+    pub fn set_instant_win_as_win(&mut self, instant_win_as_win: bool) {
+        self.rules.instant_win_as_win = instant_win_as_win;
+    }
+
+    /// Counts only need to fit the tile, 7 is a 15×15 square
+    pub const MAX_CLUE_RADIUS: u8 = 7;
+
+    pub fn clue_radius(&self) -> u8 {
+        self.rules.clue_radius
+    }
+
+    /// Variant where open tiles count the mines up to `radius` tiles away in both axes, see
+    /// [`Minefield::region_mine_count`], clamped to `1..=`[`Game::MAX_CLUE_RADIUS`]. Zeros still flood their adjacent
+    /// tiles and the game is still won by opening every safe tile. Only change it before the first move.
+    ///
+    /// Chording, [`Game::chord_flag`] and the other helpers that compare a count with its neighbors use the same
+    /// region, see [`Game::iter_neighbor_cells`]. The helpers driven by a [`Solver`] do nothing with a radius other
+    /// than 1 since [`Observation`] and the solvers assume adjacent counts. [`AnyTile::code`] caps counts at 8.
+    pub fn set_clue_radius(&mut self, radius: u8) {
+        self.rules.clue_radius = radius.clamp(1, Self::MAX_CLUE_RADIUS);
+    }
+
+    /// Clues count the adjacent tiles, what chording and the count helpers rely on
+    fn has_adjacent_clues(&self) -> bool {
+        self.rules.clue_radius == 1
+    }
+
+    /// Count shown when the tile at `coords` is opened
+    fn clue_at(&self, coords: Ix2) -> u8 {
+        if self.has_adjacent_clues() {
+            self.minefield.get_count(coords)
+        } else {
+            self.minefield
+                .region_mine_count(coords, self.rules.clue_radius)
+        }
+    }
+
+    /// Whether `count` more flags can be placed, always true unless [`Game::set_max_flags_enforced`] is set
+    fn can_add_flags(&self, count: u8) -> bool {
+        !self.rules.max_flags_enforced || self.mines_left() >= isize::from(count)
+    }
+
+    /// Flag the closed and questioned tiles of `mines` in order until [`Game::can_add_flags`] runs out, returns whether
+    /// any was flagged
+    fn flag_tiles(&mut self, mines: impl IntoIterator<Item = Ix2>) -> bool {
+        use AnyTile::*;
+
+        let mut changed = false;
+        for coords in mines {
+            if !matches!(self.grid[coords.convert()], Closed | Question) {
+                continue;
+            }
+            if !self.can_add_flags(1) {
+                break;
+            }
+            self.grid[coords.convert()] = Flag;
+            self.flag_count += 1;
+            changed = true;
         }
+        changed
+    }
+
+    /// How many mines were opened in lenient mode
+    pub fn mistakes(&self) -> Ax {
+        self.mistakes
+    }
+
+    /// Every mine opened by the move that lost the game, a chord can open more than one, empty unless lost
+    pub fn exploded_mines(&self) -> &[Ix2] {
+        &self.exploded_mines
+    }
+
+    /// Every mine ordered by distance from the nearest of [`Game::exploded_mines`], so a loss animation can spread out
+    /// from the explosion, the exploded mines come first. Ties and games that weren't lost are in row-major order.
+    pub fn mine_reveal_order(&self) -> Vec<Ix2> {
+        let distance = |coords: Ix2| {
+            self.exploded_mines
+                .iter()
+                .map(|&exploded| {
+                    let dx = u32::from(coords.0.abs_diff(exploded.0));
+                    let dy = u32::from(coords.1.abs_diff(exploded.1));
+                    dx * dx + dy * dy
+                })
+                .min()
+                .unwrap_or(0)
+        };
+        let mut mines: Vec<Ix2> = self
+            .minefield
+            .iter_cells()
+            .filter(|&(_, mine)| mine)
+            .map(|(coords, _)| coords)
+            .collect();
+        mines.sort_by_cached_key(|&coords| distance(coords));
+        mines
     }
 
     pub fn cur_state(&self) -> GameState {
@@ -254,6 +1050,18 @@ impl Game {
         self.state.is_final()
     }
 
+    /// Whether every safe tile can still be opened, that is until the game ends since the minefield never changes, a
+    /// lenient game stays winnable after a mistake
+    pub fn is_winnable(&self) -> bool {
+        !self.ended()
+    }
+
+    /// Whether the game can still be won without opening a mine, same as [`Game::is_winnable`] except a lenient game
+    /// stops being winnable this way after its first mistake
+    pub fn is_winnable_without_mistakes(&self) -> bool {
+        self.is_winnable() && self.mistakes == 0
+    }
+
     pub fn size(&self) -> Ix2 {
         self.minefield.size()
     }
@@ -262,28 +1070,72 @@ impl Game {
         self.minefield.count
     }
 
+    /// How many flags are placed, right or wrong
+    pub fn flag_count(&self) -> Ax {
+        self.flag_count.0
+    }
+
     pub fn tile_at(&self, coords: Ix2) -> AnyTile {
         self.grid[coords.convert()]
     }
 
+    /// Count of an open tile, `None` for any other tile
+    pub fn count_at(&self, coords: Ix2) -> Option<u8> {
+        match self.tile_at(coords) {
+            AnyTile::Open(count) => Some(count),
+            _ => None,
+        }
+    }
+
+    pub fn is_open_at(&self, coords: Ix2) -> bool {
+        matches!(self.tile_at(coords), AnyTile::Open(_))
+    }
+
+    pub fn is_flagged_at(&self, coords: Ix2) -> bool {
+        self.tile_at(coords) == AnyTile::Flag
+    }
+
+    /// Whether the tile is closed without a flag or question mark
+    pub fn is_closed_at(&self, coords: Ix2) -> bool {
+        self.tile_at(coords) == AnyTile::Closed
+    }
+
+    /// [`AnyTile::code`] of a tile
+    pub fn cell_code_at(&self, coords: Ix2) -> u8 {
+        self.tile_at(coords).code()
+    }
+
+    /// [`AnyTile::code`] of every tile, indexed like the grid by `(x, y)`
+    pub fn render_codes(&self) -> Array2<u8> {
+        self.grid.map(|tile| tile.code())
+    }
+
+    /// The board fully solved whatever the current state, every safe tile open with its count and every mine flagged,
+    /// indexed like the grid by `(x, y)`
+    pub fn solution_view(&self) -> Array2<AnyTile> {
+        Array2::from_shape_fn(self.grid.dim(), |(x, y)| {
+            let coords = (x as Ix, y as Ix);
+            if self.minefield[coords] {
+                AnyTile::Flag
+            } else {
+                AnyTile::Open(self.clue_at(coords))
+            }
+        })
+    }
+
+    /// Every tile with its coordinates, in row-major order
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Ix2, AnyTile)> + '_ {
+        iter_coords(self.size()).map(|coords| (coords, self.tile_at(coords)))
+    }
+
     pub fn is_tile_playable(&self, coords: Ix2) -> bool {
         use AnyTile::*;
         match self.tile_at(coords) {
             Closed => true,
-            Open(count) if count == 0 => false,
+            Open(0) => false,
             Open(count) => {
-                let mut adjacent_count = 0;
-                for pos in self.minefield.mines.iter_adjacent(coords) {
-                    let adjacent_tile = self.grid[pos.convert()];
-                    match adjacent_tile {
-                        Flag => {
-                            adjacent_count += 1;
-                        }
-                        Open(_) => continue,
-                        _ => return true,
-                    }
-                }
-                adjacent_count != count
+                let summary = self.neighbor_summary(coords);
+                summary.questioned + summary.closed > 0 || summary.flagged != count
             }
             Flag => true,
             Question => true,
@@ -320,9 +1172,33 @@ impl Game {
         }
     }
 
-    /// How many mines have not been flagged yet
+    /// Same as [`Game::elapsed_secs`] in milliseconds, saturates after about 49 days
+    pub fn elapsed_millis(&self, now: DateTime<Utc>) -> u32 {
+        if let Some(started_at) = self.started_at {
+            (self.ended_at.unwrap_or(now) - started_at)
+                .num_milliseconds()
+                .clamp(0, u32::MAX.into()) as u32
+        } else {
+            0
+        }
+    }
+
+    /// Collect the numbers of the game so far, usually called once it ended
+    pub fn report(&self, now: DateTime<Utc>) -> GameReport {
+        GameReport {
+            state: self.state,
+            config: self.minefield.game_config(),
+            elapsed_secs: self.elapsed_secs(now),
+            opened: self.open_count.0,
+            flags: self.flag_count.0,
+            mistakes: self.mistakes,
+            bbbv: self.minefield.bbbv(),
+        }
+    }
+
+    /// How many mines have not been flagged yet, mistakes count as found
     pub fn mines_left(&self) -> isize {
-        (self.minefield.count as isize) - (self.flag_count.0 as isize)
+        (self.minefield.count as isize) - (self.flag_count.0 as isize) - (self.mistakes as isize)
     }
 
     /// Flag a tile, do not consider question marker (unmark question if tile has one)
@@ -335,20 +1211,46 @@ impl Game {
         self.do_flag_question(coords, true)
     }
 
+    /// Remove the flag of a tile, any other tile is left as is
+    pub fn unflag(&mut self, coords: Ix2) -> Result<FlagOutcome> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_final()?;
+
+        if self.grid[coords.convert()] != AnyTile::Flag {
+            return Ok(FlagOutcome::NoChange);
+        }
+        self.grid[coords.convert()] = AnyTile::Closed;
+        self.flag_count -= 1;
+        Ok(FlagOutcome::MarkChanged)
+    }
+
     pub fn chord_flag(&mut self, coords: Ix2) -> Result<FlagOutcome> {
         use AnyTile::*;
         use FlagOutcome::*;
-        let Open(count) = self.grid[coords.convert()] else {
-            return Ok(NoChange);
-        };
-        if count != self.count_closed(coords) {
-            return Ok(NoChange);
+
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_in_progress()?;
+
+        let Open(count) = self.grid[coords.convert()] else {
+            return Ok(NoChange);
+        };
+        let summary = self.neighbor_summary(coords);
+        if count != summary.unopened() {
+            return Ok(NoChange);
         }
-        for pos in self.minefield.mines.iter_adjacent(coords) {
-            if matches!(self.grid[pos.convert()], Closed | Question) {
-                self.grid[pos.convert()] = Flag;
-                self.flag_count += 1;
-            }
+        if !self.can_add_flags(summary.questioned + summary.closed) {
+            return Ok(NoChange);
+        }
+        let hidden: Vec<Ix2> = self
+            .iter_neighbor_cells(coords)
+            .filter(|&(_, tile)| matches!(tile, Closed | Question))
+            .map(|(pos, _)| pos)
+            .collect();
+        for pos in hidden {
+            self.grid[pos.convert()] = Flag;
+            self.flag_count += 1;
         }
         Ok(MarkChanged)
     }
@@ -359,9 +1261,11 @@ impl Game {
 
         let coords = self.minefield.validate_coords(coords)?;
 
-        self.check_in_progress()?;
+        // marks can change before the first move, e.g. the flags of Game::with_flags
+        self.check_final()?;
 
         Ok(match self.grid[coords.convert()] {
+            Closed if !self.can_add_flags(1) => NoChange,
             Closed => {
                 self.grid[coords.convert()] = Flag;
                 self.flag_count += 1;
@@ -380,36 +1284,96 @@ impl Game {
         })
     }
 
-    fn count_flagged(&self, coords: Ix2) -> u8 {
-        self.minefield
-            .mines
-            .iter_adjacent(coords)
-            .filter(|&pos| self.grid[pos.convert()] == AnyTile::Flag)
-            .count()
-            .try_into()
-            .unwrap()
+    /// Flag every closed or questioned tile the solver proves to be a mine, tiles that are only likely mines are left
+    /// alone. Stops once no flag is left with [`Game::set_max_flags_enforced`]. Does nothing with a clue radius other
+    /// than 1, the solvers only read adjacent counts.
+    pub fn flag_proven_mines(&mut self, solver: &impl Solver) -> Result<FlagOutcome> {
+        self.check_in_progress()?;
+        if !self.has_adjacent_clues() {
+            return Ok(FlagOutcome::NoChange);
+        }
+
+        let mut outcome = FlagOutcome::NoChange;
+        loop {
+            let deductions = solver.deduce(&Observation::from_game(self));
+            if !self.flag_tiles(deductions.mines) {
+                return Ok(outcome);
+            }
+            outcome = FlagOutcome::MarkChanged;
+        }
     }
 
-    fn count_closed(&self, coords: Ix2) -> u8 {
-        self.minefield
-            .mines
-            .iter_adjacent(coords)
-            .filter(|&pos| !matches!(self.grid[pos.convert()], AnyTile::Open(_)))
-            .count()
-            .try_into()
-            .unwrap()
+    /// Unopened tile the solver proves safe closest to `coords` (by Chebyshev distance), e.g. the last tile the player
+    /// touched, so the hint is about the part of the board they're looking at. Without a proven safe tile it's the
+    /// closest of the [`Observation::safest_tiles`]. Ties go to the first in row-major order, `None` once the game
+    /// ended or with a clue radius other than 1.
+    pub fn hint_near(&self, solver: &impl Solver, coords: Ix2) -> Option<Ix2> {
+        if self.ended() || !self.has_adjacent_clues() {
+            return None;
+        }
+        let distance = |(x, y): Ix2| x.abs_diff(coords.0).max(y.abs_diff(coords.1));
+        let obs = Observation::from_game(self);
+        let mut safe = solver.deduce(&obs).safe;
+        if safe.is_empty() {
+            safe = obs.safest_tiles();
+        }
+        safe.into_iter()
+            .min_by_key(|&(x, y)| (distance((x, y)), y, x))
     }
 
-    fn has_adjacent_question(&self, coords: Ix2) -> bool {
-        self.minefield
-            .mines
-            .iter_adjacent(coords)
-            .map(|pos| self.grid[pos.convert()])
-            .any(|tile| tile == AnyTile::Question)
+    /// Remove every flag, open tiles are left untouched
+    pub fn clear_flags(&mut self) -> Result<FlagOutcome> {
+        self.clear_marks(AnyTile::Flag)
+    }
+
+    /// Remove every question mark, open tiles are left untouched
+    pub fn clear_questions(&mut self) -> Result<FlagOutcome> {
+        self.clear_marks(AnyTile::Question)
+    }
+
+    fn clear_marks(&mut self, mark: AnyTile) -> Result<FlagOutcome> {
+        self.check_final()?;
+
+        let mut outcome = FlagOutcome::NoChange;
+        for tile in self.grid.iter_mut().filter(|tile| **tile == mark) {
+            *tile = AnyTile::Closed;
+            outcome = FlagOutcome::MarkChanged;
+        }
+        if mark == AnyTile::Flag {
+            self.flag_count = Saturating(0);
+        }
+        Ok(outcome)
+    }
+
+    /// Tiles counted by the clue at `coords` with their coordinates, in row-major order. These are the adjacent tiles
+    /// unless [`Game::set_clue_radius`] widened the clues.
+    pub fn iter_neighbor_cells(&self, coords: Ix2) -> impl Iterator<Item = (Ix2, AnyTile)> + '_ {
+        iter_region(coords, self.rules.clue_radius, self.size())
+            .map(|pos| (pos, self.grid[pos.convert()]))
+    }
+
+    /// Count the neighbors of a tile by kind in a single pass, the same tiles as [`Game::iter_neighbor_cells`]
+    pub fn neighbor_summary(&self, coords: Ix2) -> NeighborSummary {
+        use AnyTile::*;
+        let mut summary = NeighborSummary::default();
+        for (_, tile) in self.iter_neighbor_cells(coords) {
+            match tile {
+                Flag | Exploded => summary.flagged += 1,
+                Question => summary.questioned += 1,
+                Open(_) => summary.open += 1,
+                Closed | Mine | IncorrectFlag => summary.closed += 1,
+            }
+        }
+        summary
     }
 
-    /// Open a closed tile, do not open neighbor tiles
+    /// Open a closed tile, do not open neighbor tiles. Flagged and questioned tiles are left alone on purpose so a
+    /// misclick can't open them, see [`Game::force_open`].
     pub fn open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_final()?;
+
         if matches!(self.grid[coords.convert()], AnyTile::Closed) {
             self.open_with_chords(coords, now)
         } else {
@@ -417,67 +1381,500 @@ impl Game {
         }
     }
 
+    /// Remove the flag or question mark of a tile and open it, opening a flagged mine explodes like any other mine
+    pub fn force_open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        match self.grid[coords.convert()] {
+            AnyTile::Flag => {
+                self.check_final()?;
+                self.grid[coords.convert()] = AnyTile::Closed;
+                self.flag_count -= 1;
+            }
+            AnyTile::Question => {
+                self.check_final()?;
+                self.grid[coords.convert()] = AnyTile::Closed;
+            }
+            _ => {}
+        }
+        self.open(coords, now)
+    }
+
+    /// Same as [`Game::open`] but also returns the opened tiles with the flood-fill depth they were opened at, 0 for the
+    /// tile itself and 1 for its neighbors and so on, in opening order. Useful to animate the flood-fill.
+    pub fn open_with_depths(
+        &mut self,
+        coords: Ix2,
+        now: DateTime<Utc>,
+    ) -> Result<(OpenOutcome, Vec<(Ix2, u16)>)> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        let mut opened = Vec::new();
+        if !matches!(self.grid[coords.convert()], AnyTile::Closed) {
+            return Ok((OpenOutcome::NoChange, opened));
+        }
+        self.check_final()?;
+        let outcome = self.open_tile_tracked(coords, now, Some(&mut opened));
+        Ok((outcome, opened))
+    }
+
+    /// Open a closed tile flooding at most `max_depth` rings of neighbors, returns whether the flood-fill has more
+    /// to open. Each call first continues the flood left by the previous one for up to `max_depth` rings, so calling
+    /// it once per frame until nothing is left ripples the flood-fill out. Opening a tile while a flood is pending
+    /// merges both, the new tile counts as one ring.
+    pub fn open_step(
+        &mut self,
+        coords: Ix2,
+        max_depth: u16,
+        now: DateTime<Utc>,
+    ) -> Result<(OpenOutcome, bool)> {
+        use OpenOutcome::*;
+
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_final()?;
+
+        let mut rings = max_depth;
+        if self.grid[coords.convert()] == AnyTile::Closed {
+            if self.minefield[coords] {
+                let outcome = self.open_tile(coords, now);
+                if self.ended() {
+                    self.flood_frontier.clear();
+                }
+                return Ok((outcome, !self.flood_frontier.is_empty()));
+            }
+            self.flood_frontier.insert(coords);
+            rings = rings.saturating_add(1);
+        }
+
+        let opened_before = self.open_count;
+        for _ in 0..rings {
+            if self.flood_frontier.is_empty() {
+                break;
+            }
+            let ring = core::mem::take(&mut self.flood_frontier);
+            self.flood_frontier = self.open_ring(ring);
+        }
+
+        if self.open_count == opened_before {
+            Ok((NoChange, !self.flood_frontier.is_empty()))
+        } else if self.open_count == Saturating(self.minefield.safe_count()) {
+            self.flood_frontier.clear();
+            self.mark_ended(true, now);
+            Ok((Win, false))
+        } else {
+            self.mark_started(now);
+            Ok((Safe, !self.flood_frontier.is_empty()))
+        }
+    }
+
+    /// Open the closed safe tiles of `ring` and return the closed neighbors of the zeros among them
+    fn open_ring(&mut self, ring: BTreeSet<Ix2>) -> BTreeSet<Ix2> {
+        let mut next = BTreeSet::new();
+        for coords in ring {
+            if self.grid[coords.convert()] != AnyTile::Closed {
+                continue;
+            }
+            let count = self.clue_at(coords);
+            self.grid[coords.convert()] = AnyTile::Open(count);
+            self.open_count += 1;
+            if count == 0 && self.rules.flood_fill {
+                next.extend(
+                    self.minefield
+                        .mines
+                        .iter_adjacent(coords)
+                        .filter(|&pos| self.grid[pos.convert()] == AnyTile::Closed),
+                );
+            }
+        }
+        next
+    }
+
     pub fn is_chordable(&self, coords: Ix2) -> bool {
         if let AnyTile::Open(count) = self.grid[coords.convert()] {
-            count == self.count_flagged(coords) && !self.has_adjacent_question(coords)
+            self.allows_chord(coords, count)
         } else {
             false
         }
     }
 
-    /// Open a tile, or try to open neighbor tiles
-    pub fn chord_open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+    /// Same as [`NeighborSummary::allows_chord`], ignoring question marks if [`Game::set_chord_ignores_questions`] is
+    /// set
+    fn allows_chord(&self, coords: Ix2, count: u8) -> bool {
+        let summary = self.neighbor_summary(coords);
+        summary.flagged == count && (self.rules.chord_ignores_questions || summary.questioned == 0)
+    }
+
+    /// All open tiles that can be chorded and still have closed neighbors to open
+    pub fn chordable_cells(&self) -> Vec<Ix2> {
+        iter_coords(self.size())
+            .filter(|&coords| self.is_chordable(coords))
+            .filter(|&coords| {
+                self.iter_neighbor_cells(coords).any(|(_, tile)| {
+                    tile == AnyTile::Closed
+                        || (tile == AnyTile::Question && self.rules.chord_ignores_questions)
+                })
+            })
+            .collect()
+    }
+
+    /// Closed and question marked tiles that an open tile forces to be mines because its count matches its unopened
+    /// neighbors, the tiles [`Game::chord_flag`] would flag, in row-major order. Cheaper than a [`Solver`] for this one
+    /// rule.
+    pub fn flaggable_cells(&self) -> Vec<Ix2> {
+        use AnyTile::*;
+        let mut flaggable = Array2::from_elem(self.grid.dim(), false);
+        for coords in iter_coords(self.size()) {
+            let Open(count) = self.grid[coords.convert()] else {
+                continue;
+            };
+            if count != self.neighbor_summary(coords).unopened() {
+                continue;
+            }
+            for (pos, tile) in self.iter_neighbor_cells(coords) {
+                if matches!(tile, Closed | Question) {
+                    flaggable[pos.convert()] = true;
+                }
+            }
+        }
+        iter_coords(self.size())
+            .filter(|&coords| flaggable[coords.convert()])
+            .collect()
+    }
+
+    /// Keep chording every chordable tile until none are left or the game ends, chording can open new chordable tiles
+    pub fn chord_all(&mut self, now: DateTime<Utc>) -> Result<OpenOutcome> {
         use OpenOutcome::*;
 
+        self.check_in_progress()?;
+
+        let mut outcome = NoChange;
+        loop {
+            let cells = self.chordable_cells();
+            if cells.is_empty() {
+                return Ok(outcome);
+            }
+            for coords in cells {
+                outcome = outcome | self.chord_open(coords, now)?;
+                if self.ended() {
+                    return Ok(outcome);
+                }
+            }
+        }
+    }
+
+    /// Keep opening the tiles the solver proves to be safe until it finds nothing new or the game ends. Does nothing
+    /// with a clue radius other than 1.
+    pub fn auto_open_trivial(
+        &mut self,
+        solver: &impl Solver,
+        now: DateTime<Utc>,
+    ) -> Result<OpenOutcome> {
+        self.check_in_progress()?;
+        Ok(self.auto_open_where(solver, now, |_| true))
+    }
+
+    /// Headless auto-player: each step flags every closed or questioned tile the solver proves to be a mine and opens
+    /// every closed tile it proves safe, steps repeat until the game ends, a step changes nothing or `max_steps` steps
+    /// were made. Mines are flagged like [`Game::flag_proven_mines`]. Always [`AutoPlayResult::Stuck`] with a clue
+    /// radius other than 1.
+    pub fn auto_play(
+        &mut self,
+        solver: &impl Solver,
+        max_steps: usize,
+        now: DateTime<Utc>,
+    ) -> Result<AutoPlayResult> {
+        use AnyTile::*;
+
+        self.check_in_progress()?;
+        if !self.has_adjacent_clues() {
+            return Ok(AutoPlayResult::Stuck);
+        }
+
+        for _ in 0..max_steps {
+            let deductions = solver.deduce(&Observation::from_game(self));
+            let mut changed = self.flag_tiles(deductions.mines);
+            for coords in deductions.safe {
+                if self.grid[coords.convert()] != Closed {
+                    continue;
+                }
+                self.open_tile(coords, now);
+                changed = true;
+                if self.ended() {
+                    break;
+                }
+            }
+            if self.ended() {
+                let won = matches!(self.state, GameState::Win | GameState::InstantWin);
+                return Ok(if won {
+                    AutoPlayResult::Won
+                } else {
+                    AutoPlayResult::Lost
+                });
+            }
+            if !changed {
+                return Ok(AutoPlayResult::Stuck);
+            }
+        }
+        Ok(AutoPlayResult::StepLimit)
+    }
+
+    /// Like [`Game::auto_open_trivial`] but only opens tiles at most `radius` tiles away from `coords`, diagonals
+    /// included, tiles opened this way can still lead to deductions anywhere within the radius
+    pub fn auto_open_trivial_near(
+        &mut self,
+        solver: &impl Solver,
+        coords: Ix2,
+        radius: u8,
+        now: DateTime<Utc>,
+    ) -> Result<OpenOutcome> {
+        let coords = self.minefield.validate_coords(coords)?;
+        self.check_in_progress()?;
+        Ok(self.auto_open_where(solver, now, |pos| {
+            pos.0.abs_diff(coords.0) <= radius && pos.1.abs_diff(coords.1) <= radius
+        }))
+    }
+
+    fn auto_open_where(
+        &mut self,
+        solver: &impl Solver,
+        now: DateTime<Utc>,
+        allowed: impl Fn(Ix2) -> bool,
+    ) -> OpenOutcome {
+        let mut outcome = OpenOutcome::NoChange;
+        if !self.has_adjacent_clues() {
+            return outcome;
+        }
+        loop {
+            let deductions = solver.deduce(&Observation::from_game(self));
+            let mut changed = false;
+            for coords in deductions.safe {
+                if self.grid[coords.convert()] != AnyTile::Closed || !allowed(coords) {
+                    continue;
+                }
+                outcome = outcome | self.open_tile(coords, now);
+                changed = true;
+                if self.ended() {
+                    return outcome;
+                }
+            }
+            if !changed {
+                return outcome;
+            }
+        }
+    }
+
+    /// Open a tile, or try to open neighbor tiles. An open tile is only chorded when its flags match its count and,
+    /// unless [`Game::set_chord_ignores_questions`] is set, no neighbor has a question mark, see [`Game::is_chordable`]
+    pub fn chord_open(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
         let coords = self.minefield.validate_coords(coords)?;
 
         self.check_final()?;
 
         Ok(match self.grid[coords.convert()] {
-            AnyTile::Open(count)
-                if count == self.count_flagged(coords) && !self.has_adjacent_question(coords) =>
-            {
+            AnyTile::Open(count) if self.allows_chord(coords, count) => {
                 self.check_in_progress()?;
                 // Perform opening of all closed neighbors when flagged count matches
-                self.minefield
-                    .mines
-                    .iter_adjacent(coords)
-                    .map(|neighbor_coords| self.open_tile(neighbor_coords, now))
-                    .reduce(BitOr::bitor)
-                    .unwrap_or(NoChange)
+                self.open_neighbors(coords, now)
             }
             // TODO: make this an error:
             _ => self.open_tile(coords, now),
         })
     }
 
-    pub fn open_with_chords(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
-        use OpenOutcome::*;
+    /// Same as [`Game::chord_open`] but also returns the outcome of each tile that changed, in order: the safe neighbors
+    /// first, then the mines hit, which all explode together
+    pub fn chord_open_detailed(
+        &mut self,
+        coords: Ix2,
+        now: DateTime<Utc>,
+    ) -> Result<(OpenOutcome, Vec<(Ix2, OpenOutcome)>)> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_final()?;
 
+        let mut steps = Vec::new();
+        let outcome = match self.grid[coords.convert()] {
+            AnyTile::Open(count) if self.allows_chord(coords, count) => {
+                self.check_in_progress()?;
+                self.open_neighbors_tracked(coords, now, Some(&mut steps))
+            }
+            _ => {
+                let outcome = self.open_tile(coords, now);
+                if outcome != OpenOutcome::NoChange {
+                    steps.push((coords, outcome));
+                }
+                outcome
+            }
+        };
+        Ok((outcome, steps))
+    }
+
+    /// Chord without flags: an open tile is chorded when its count matches its flags plus the neighbors `solver` proves
+    /// to be mines, and then only opens the closed neighbors that aren't proven mines. Other tiles are opened like
+    /// [`Game::chord_open`] does.
+    pub fn chord_open_deduced(
+        &mut self,
+        solver: &impl Solver,
+        coords: Ix2,
+        now: DateTime<Utc>,
+    ) -> Result<OpenOutcome> {
+        let coords = self.minefield.validate_coords(coords)?;
+
+        self.check_final()?;
+
+        let AnyTile::Open(count) = self.grid[coords.convert()] else {
+            return Ok(self.open_tile(coords, now));
+        };
+        self.check_in_progress()?;
+        if !self.has_adjacent_clues() {
+            return Ok(OpenOutcome::NoChange);
+        }
+        let proven = solver.deduce(&Observation::from_game(self)).mines;
+        let is_mine = |pos: Ix2, tile: AnyTile| {
+            matches!(tile, AnyTile::Flag | AnyTile::Exploded) || proven.contains(&pos)
+        };
+        let mines = self
+            .iter_neighbor_cells(coords)
+            .filter(|&(pos, tile)| is_mine(pos, tile))
+            .count();
+        if mines != usize::from(count) {
+            return Ok(OpenOutcome::NoChange);
+        }
+        let to_open: Vec<Ix2> = self
+            .iter_neighbor_cells(coords)
+            .filter(|&(pos, tile)| tile == AnyTile::Closed && !is_mine(pos, tile))
+            .map(|(pos, _)| pos)
+            .collect();
+        let mut outcome = OpenOutcome::NoChange;
+        for pos in to_open {
+            outcome = outcome | self.open_tile(pos, now);
+            if self.ended() {
+                break;
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Resolve the last hidden neighbor of an open tile, a closed or question marked one: it is flagged when the count
+    /// is one more than the flags around and [`Game::set_max_flags_enforced`] leaves a flag, opened when the flags
+    /// already match. Anything else, including tiles that aren't open, is left alone. Returns what was opened and what
+    /// was flagged, only one of them can change.
+    pub fn resolve_obvious(
+        &mut self,
+        coords: Ix2,
+        now: DateTime<Utc>,
+    ) -> Result<(OpenOutcome, FlagOutcome)> {
+        use AnyTile::*;
+
+        let coords = self.minefield.validate_coords(coords)?;
+        let unchanged = (OpenOutcome::NoChange, FlagOutcome::NoChange);
+        let Open(count) = self.grid[coords.convert()] else {
+            return Ok(unchanged);
+        };
+        self.check_in_progress()?;
+        let summary = self.neighbor_summary(coords);
+        if summary.questioned + summary.closed != 1 {
+            return Ok(unchanged);
+        }
+        let Some((hidden, _)) = self
+            .iter_neighbor_cells(coords)
+            .find(|&(_, tile)| matches!(tile, Closed | Question))
+        else {
+            return Ok(unchanged);
+        };
+        Ok(match count.checked_sub(summary.flagged) {
+            Some(0) => {
+                self.grid[hidden.convert()] = Closed;
+                (self.open_tile(hidden, now), FlagOutcome::NoChange)
+            }
+            Some(1) if self.flag_tiles([hidden]) => {
+                (OpenOutcome::NoChange, FlagOutcome::MarkChanged)
+            }
+            _ => unchanged,
+        })
+    }
+
+    pub fn open_with_chords(&mut self, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
         let coords = self.minefield.validate_coords(coords)?;
 
         self.check_final()?;
 
         Ok(match self.grid[coords.convert()] {
-            AnyTile::Open(count)
-                if count == self.count_flagged(coords) && !self.has_adjacent_question(coords) =>
-            {
+            AnyTile::Open(count) if self.allows_chord(coords, count) => {
                 self.check_in_progress()?;
                 // Perform opening of all closed neighbors when flagged count matches
-                self.minefield
-                    .mines
-                    .iter_adjacent(coords)
-                    .map(|neighbor_coords| self.open_tile(neighbor_coords, now))
-                    .reduce(BitOr::bitor)
-                    .unwrap_or(NoChange)
+                self.open_neighbors(coords, now)
             }
             _ => self.open_tile(coords, now),
         })
     }
 
+    /// Open all neighbors, safe ones first so every mine hit explodes together
+    fn open_neighbors(&mut self, coords: Ix2, now: DateTime<Utc>) -> OpenOutcome {
+        self.open_neighbors_tracked(coords, now, None)
+    }
+
+    /// Same as [`Game::open_neighbors`], pushing the outcome of each neighbor that changed to `steps` if given
+    fn open_neighbors_tracked(
+        &mut self,
+        coords: Ix2,
+        now: DateTime<Utc>,
+        mut steps: Option<&mut Vec<(Ix2, OpenOutcome)>>,
+    ) -> OpenOutcome {
+        use OpenOutcome::*;
+
+        let mut outcome = NoChange;
+        let mut hit = Vec::new();
+        let neighbors: Vec<Ix2> = self
+            .iter_neighbor_cells(coords)
+            .map(|(pos, _)| pos)
+            .collect();
+        for neighbor_coords in neighbors {
+            let tile = &mut self.grid[neighbor_coords.convert()];
+            if *tile == AnyTile::Question && self.rules.chord_ignores_questions {
+                *tile = AnyTile::Closed;
+            }
+            let is_closed = *tile == AnyTile::Closed;
+            if is_closed && self.minefield[neighbor_coords] && !self.rules.lenient {
+                hit.push(neighbor_coords);
+            } else {
+                let neighbor_outcome = self.open_tile(neighbor_coords, now);
+                if let Some(steps) = steps.as_deref_mut() {
+                    if neighbor_outcome != NoChange {
+                        steps.push((neighbor_coords, neighbor_outcome));
+                    }
+                }
+                outcome = outcome | neighbor_outcome;
+            }
+        }
+        if !hit.is_empty() {
+            for &mine_coords in &hit {
+                self.grid[mine_coords.convert()] = AnyTile::Exploded;
+                if let Some(steps) = steps.as_deref_mut() {
+                    steps.push((mine_coords, Explode));
+                }
+            }
+            self.exploded_mines = hit;
+            self.mark_ended(false, now);
+            outcome = outcome | Explode;
+        }
+        outcome
+    }
+
     /// Helper function to open a single tile and perform flood-fill if necessary
     fn open_tile(&mut self, coords: Ix2, now: DateTime<Utc>) -> OpenOutcome {
-        use alloc::collections::{BTreeSet, VecDeque};
+        self.open_tile_tracked(coords, now, None)
+    }
+
+    /// Same as [`Game::open_tile`], pushing each opened safe tile with its flood-fill depth to `opened` if given
+    fn open_tile_tracked(
+        &mut self,
+        coords: Ix2,
+        now: DateTime<Utc>,
+        mut opened: Option<&mut Vec<(Ix2, u16)>>,
+    ) -> OpenOutcome {
+        use alloc::collections::VecDeque;
         use AnyTile::*;
         use OpenOutcome::*;
 
@@ -485,24 +1882,38 @@ impl Game {
         let mine = self.minefield[coords];
 
         match (tile, mine) {
+            (Closed, true) if self.rules.lenient => {
+                self.grid[coords.convert()] = Exploded;
+                self.mistakes += 1;
+                log::debug!("Mistake at {:?}, total: {}", coords, self.mistakes);
+                self.mark_started(now);
+                Explode
+            }
             (Closed, true) => {
                 self.grid[coords.convert()] = Exploded;
+                self.exploded_mines = alloc::vec![coords];
                 self.mark_ended(false, now);
                 Explode
             }
             (Closed, false) => {
-                let count = self.minefield.get_count(coords);
+                let count = self.clue_at(coords);
                 self.grid[coords.convert()] = Open(count);
                 self.open_count += 1;
                 log::debug!("Open tile at {:?}, mine count: {}", coords, count);
+                if let Some(opened) = opened.as_deref_mut() {
+                    opened.push((coords, 0));
+                }
 
-                if count == 0 {
+                if count == 0 && self.rules.flood_fill {
+                    // checked once, the per tile traces below are in the hot loop of bulk simulations
+                    let trace = log::log_enabled!(log::Level::Trace);
                     let mut visited = BTreeSet::from([coords]);
                     let mut to_visit: VecDeque<_> = self
                         .minefield
                         .mines
                         .iter_adjacent(coords)
                         .filter(|&pos| matches!(self.grid[pos.convert()], Closed))
+                        .map(|pos| (pos, 1))
                         .collect();
                     log::trace!(
                         "Starting flood-fill from {:?}, initial neighbors: {:?}",
@@ -510,26 +1921,33 @@ impl Game {
                         to_visit
                     );
 
-                    while let Some(visit_coords) = to_visit.pop_front() {
+                    while let Some((visit_coords, depth)) = to_visit.pop_front() {
                         if !visited.insert(visit_coords) {
                             continue;
                         }
 
                         // skip flagged or already opened tiles
                         if matches!(self.grid[visit_coords.convert()], Open(_) | Flag) {
-                            log::trace!("Skipping tile at {:?}", visit_coords);
+                            if trace {
+                                log::trace!("Skipping tile at {:?}", visit_coords);
+                            }
                             continue;
                         }
 
                         // open visited tiles
-                        let visit_count = self.minefield.get_count(visit_coords);
+                        let visit_count = self.clue_at(visit_coords);
                         self.grid[visit_coords.convert()] = Open(visit_count);
                         self.open_count += 1;
-                        log::trace!(
-                            "Flood opened tile at {:?}, mine count: {}",
-                            visit_coords,
-                            visit_count
-                        );
+                        if trace {
+                            log::trace!(
+                                "Flood opened tile at {:?}, mine count: {}",
+                                visit_coords,
+                                visit_count
+                            );
+                        }
+                        if let Some(opened) = opened.as_deref_mut() {
+                            opened.push((visit_coords, depth));
+                        }
 
                         // if this is also zero we visit the neighbors
                         if visit_count == 0 {
@@ -538,7 +1956,8 @@ impl Game {
                                     .mines
                                     .iter_adjacent(visit_coords)
                                     .filter(|&pos| matches!(self.grid[pos.convert()], Closed))
-                                    .filter(|pos| !visited.contains(pos)),
+                                    .filter(|pos| !visited.contains(pos))
+                                    .map(|pos| (pos, depth + 1)),
                             );
                         }
                     }
@@ -568,6 +1987,7 @@ impl Game {
     /// Checks for wrong flags and unflagged mines after game ends
     fn mark_ended(&mut self, won: bool, now: DateTime<Utc>) {
         use GameState::*;
+        let first_move = self.state.is_initial();
         match (self.state, won) {
             (Win, false) => {
                 self.state = Lose;
@@ -589,6 +2009,9 @@ impl Game {
             (InProgress, false) => {
                 self.state = Lose;
             }
+            (NotStarted, true) if self.rules.instant_win_as_win => {
+                self.state = Win;
+            }
             (NotStarted, true) => {
                 self.state = InstantWin;
             }
@@ -598,7 +2021,7 @@ impl Game {
         }
         self.ended_at.replace(now);
         log::debug!("ended at {}", now);
-        if matches!(self.state, InstantWin | InstantLoss) {
+        if first_move {
             log::debug!("started at {}", now);
             self.started_at.replace(now);
         }
@@ -607,27 +2030,1398 @@ impl Game {
 
     fn reveal_mines(&mut self, won: bool) {
         use AnyTile::*;
-        let (x_end, y_end) = self.minefield.size();
-        for x in 0..x_end {
-            for y in 0..y_end {
-                let coords = (x, y);
-                let tile = self.grid[coords.convert()];
-                let mine = self.minefield[coords];
-                if mine {
-                    if tile == Closed || tile == Question {
-                        if won {
+        for coords in iter_coords(self.minefield.size()) {
+            let tile = self.grid[coords.convert()];
+            let mine = self.minefield[coords];
+            if mine {
+                if tile == Closed || tile == Question {
+                    if won {
+                        if self.rules.auto_flag_on_win {
                             self.grid[coords.convert()] = Flag;
                             self.flag_count += 1;
-                        } else {
-                            self.grid[coords.convert()] = Mine;
                         }
+                    } else {
+                        self.grid[coords.convert()] = Mine;
                     }
-                } else {
-                    if tile == Flag {
-                        self.grid[coords.convert()] = IncorrectFlag;
-                    }
+                }
+            } else {
+                if tile == Flag {
+                    self.grid[coords.convert()] = IncorrectFlag;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+    use alloc::vec;
+
+    pub(crate) fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(0, 0).unwrap()
+    }
+
+    /// Build a minefield from rows of `*` (mine) and `.` (safe), each row is one `y`
+    pub(crate) fn field(rows: &[&str]) -> Minefield {
+        let mut mines = Array2::default((rows[0].len(), rows.len()));
+        let mut count = 0;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                if c == '*' {
+                    mines[(x, y)] = true;
+                    count += 1;
+                }
+            }
+        }
+        Minefield { mines, count }
+    }
+
+    #[test]
+    fn restart_keeps_minefield() {
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.open((2, 2), now()).unwrap();
+        game.restart();
+        assert_eq!(game.cur_state(), GameState::NotStarted);
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Closed);
+        assert_eq!(game.open((0, 0), now()).unwrap(), OpenOutcome::Explode);
+    }
+
+    #[test]
+    fn restart_regenerated_first_move_is_safe() {
+        let config = GameConfig::new((9, 9), 10);
+        for seed in 0..20 {
+            let mut game = Game::new(
+                RandomMinefieldGenerator::new(seed, (0, 0), StartTile::Random).generate(config),
+            );
+            let first_move = (seed as Ix % 9, 8 - seed as Ix % 9);
+            game.restart_regenerated(
+                |coords| RandomMinefieldGenerator::new(seed + 100, coords, StartTile::AlwaysZero),
+                first_move,
+            )
+            .unwrap();
+            assert_eq!(game.total_mines(), 10);
+            assert_ne!(game.open(first_move, now()).unwrap(), OpenOutcome::Explode);
+            assert_eq!(game.tile_at(first_move), AnyTile::Open(0));
+        }
+
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        assert!(matches!(
+            game.restart_regenerated(
+                |coords| RandomMinefieldGenerator::new(0, coords, StartTile::AlwaysZero),
+                (3, 0),
+            ),
+            Err(GameError::InvalidCoords)
+        ));
+    }
+
+    #[test]
+    fn validate_rejects_wrong_mine_count() {
+        let mut minefield = field(&["*..", "...", "..*"]);
+        assert!(minefield.validate().is_ok());
+        minefield.count = 3;
+        assert!(matches!(
+            minefield.validate(),
+            Err(GameError::MineCountMismatch)
+        ));
+        assert!(matches!(
+            Game::new(minefield).validate(),
+            Err(GameError::MineCountMismatch)
+        ));
+    }
+
+    #[test]
+    fn chord_all_clears_flagged_board() {
+        let mut game = Game::new(field(&["*...", "....", "...*", "*..."]));
+        game.open((2, 0), now()).unwrap();
+        assert_eq!(game.cur_state(), GameState::InProgress);
+        game.flag((0, 0)).unwrap();
+        game.flag((3, 2)).unwrap();
+        game.flag((0, 3)).unwrap();
+        assert!(!game.chordable_cells().is_empty());
+        assert_eq!(game.chord_all(now()).unwrap(), OpenOutcome::Win);
+        assert!(game.chordable_cells().is_empty());
+    }
+
+    #[test]
+    fn from_binary_rows_parses_layout() {
+        let minefield = Minefield::from_binary_rows(&["010", " 0 0 1", "100", ""]).unwrap();
+        assert_eq!(minefield.size(), (3, 3));
+        assert_eq!(minefield.count, 3);
+        assert!(minefield[(1, 0)] && minefield[(2, 1)] && minefield[(0, 2)]);
+        assert!(matches!(
+            Minefield::from_binary_rows(&["012"]),
+            Err(GameError::InvalidLayoutCharacter)
+        ));
+        assert!(matches!(
+            Minefield::from_binary_rows(&["01", "1"]),
+            Err(GameError::InvalidBoardShape)
+        ));
+        assert!(matches!(
+            Minefield::from_binary_rows(&[]),
+            Err(GameError::InvalidBoardShape)
+        ));
+    }
+
+    /// Build an observation from rows of `#` (closed), `F` (flag), `?` (question) and digits (open)
+    pub(crate) fn obs(rows: &[&str], mines: Ax) -> Observation {
+        let mut grid = Array2::default((rows[0].len(), rows.len()));
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                grid[(x, y)] = match c {
+                    '#' => AnyTile::Closed,
+                    'F' => AnyTile::Flag,
+                    '?' => AnyTile::Question,
+                    d => AnyTile::Open(d.to_digit(10).unwrap() as u8),
+                };
+            }
+        }
+        Observation::new(grid, mines).unwrap()
+    }
+
+    #[test]
+    fn zero_cells_with_corner_mine() {
+        let minefield = field(&["*...", "....", "....", "...."]);
+        let zeros = minefield.zero_cells();
+        assert!(zeros.contains(&(3, 0)) && zeros.contains(&(0, 3)) && zeros.contains(&(3, 3)));
+        assert!(!zeros.contains(&(0, 0)) && !zeros.contains(&(1, 1)));
+        assert_eq!(minefield.safe_cells().len(), 15);
+    }
+
+    #[test]
+    fn serialized_names_are_pinned() {
+        use GameState::*;
+
+        for (state, name) in [
+            (NotStarted, "\"not_started\""),
+            (InProgress, "\"in_progress\""),
+            (Win, "\"win\""),
+            (Lose, "\"lose\""),
+            (InstantWin, "\"instant_win\""),
+            (InstantLoss, "\"instant_loss\""),
+        ] {
+            assert_eq!(serde_json::to_string(&state).unwrap(), name);
+            assert_eq!(serde_json::from_str::<GameState>(name).unwrap(), state);
+            assert_eq!(name.trim_matches('"'), state.as_str());
+        }
+        assert_eq!(
+            serde_json::from_str::<GameState>("\"InProgress\"").unwrap(),
+            InProgress
+        );
+        assert_eq!(
+            serde_json::from_str::<GameState>("\"InstantLoss\"").unwrap(),
+            InstantLoss
+        );
+
+        for outcome in [
+            OpenOutcome::NoChange,
+            OpenOutcome::Safe,
+            OpenOutcome::Explode,
+            OpenOutcome::Win,
+        ] {
+            let json = serde_json::to_string(&outcome).unwrap();
+            assert_eq!(json.trim_matches('"'), outcome.as_str());
+            assert_eq!(serde_json::from_str::<OpenOutcome>(&json).unwrap(), outcome);
+        }
+        assert_eq!(
+            serde_json::to_string(&FlagOutcome::MarkChanged).unwrap(),
+            "\"mark_changed\""
+        );
+        assert_eq!(
+            serde_json::to_string(&FlagOutcome::NoChange).unwrap(),
+            "\"no_change\""
+        );
+    }
+
+    #[test]
+    fn clustered_mines_have_higher_density() {
+        let clustered = field(&["**...", "**...", ".....", ".....", "....."]);
+        let spread = field(&["*...*", ".....", ".....", ".....", "*...*"]);
+        assert_eq!(clustered.local_density((0, 0), 1), 1.0);
+        assert_eq!(clustered.local_density((2, 2), 0), 0.0);
+        assert_eq!(spread.max_local_density(1), 0.25);
+        assert!(clustered.max_local_density(1) > spread.max_local_density(1));
+    }
+
+    #[test]
+    fn auto_open_trivial_wins_solvable_board() {
+        let mut game = Game::new(field(&["*.*.", "....", "....", "...."]));
+        game.open((3, 3), now()).unwrap();
+        assert_eq!(game.cur_state(), GameState::InProgress);
+        let outcome = game
+            .auto_open_trivial(&TrivialSolver.then(SubsetSolver), now())
+            .unwrap();
+        assert_eq!(outcome, OpenOutcome::Win);
+    }
+
+    #[test]
+    fn lenient_mistakes_keep_game_going() {
+        let mut game = Game::new_lenient(field(&["*..", "...", "..*"]));
+        assert_eq!(game.open((0, 0), now()).unwrap(), OpenOutcome::Explode);
+        assert_eq!(game.cur_state(), GameState::InProgress);
+        assert_eq!(game.open((2, 2), now()).unwrap(), OpenOutcome::Explode);
+        assert_eq!(game.cur_state(), GameState::InProgress);
+        assert_eq!(game.mistakes(), 2);
+        assert_eq!(game.mines_left(), 0);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Exploded);
+        game.open((1, 0), now()).unwrap();
+        assert_eq!(
+            game.auto_open_trivial(&TrivialSolver, now()).unwrap(),
+            OpenOutcome::Win
+        );
+        game.restart();
+        assert!(game.is_lenient());
+        assert_eq!(game.mistakes(), 0);
+    }
+
+    #[test]
+    fn try_new_enforces_area_cap() {
+        assert!(GameConfig::try_new((99, 99), 10).is_ok());
+        assert!(matches!(
+            GameConfig::try_new((100, 99), 10),
+            Err(GameError::InvalidCoords)
+        ));
+        assert!(matches!(
+            GameConfig::try_new((255, 255), 10),
+            Err(GameError::InvalidCoords)
+        ));
+        assert!(matches!(
+            GameConfig::try_new((0, 9), 10),
+            Err(GameError::InvalidBoardShape)
+        ));
+        assert!(matches!(
+            GameConfig::try_new((3, 3), 10),
+            Err(GameError::TooManyMines)
+        ));
+    }
+
+    #[test]
+    fn neighbor_array_positions() {
+        let minefield = field(&["...", "...", "..."]);
+        let (neighbors, count) = minefield.neighbor_array((0, 0));
+        assert_eq!(count, 3);
+        assert_eq!(
+            neighbors,
+            [
+                None,
+                None,
+                None,
+                None,
+                Some((1, 0)),
+                None,
+                Some((0, 1)),
+                Some((1, 1))
+            ]
+        );
+        let (neighbors, count) = minefield.neighbor_array((1, 1));
+        assert_eq!(count, 8);
+        assert!(neighbors.iter().all(Option::is_some));
+        let (neighbors, count) = minefield.neighbor_array((2, 2));
+        assert_eq!(count, 3);
+        assert_eq!(
+            neighbors[..5],
+            [Some((1, 1)), Some((2, 1)), None, Some((1, 2)), None]
+        );
+    }
+
+    #[test]
+    fn iter_cells_is_row_major() {
+        let minefield = field(&["*..", "..*"]);
+        let cells: Vec<_> = minefield.iter_cells().collect();
+        assert_eq!(cells.len(), usize::from(minefield.total_tiles()));
+        assert_eq!(cells[0], ((0, 0), true));
+        assert_eq!(cells[1], ((1, 0), false));
+        assert_eq!(cells[3], ((0, 1), false));
+        assert_eq!(cells[5], ((2, 1), true));
+
+        let mut game = Game::new(minefield);
+        game.open((0, 1), now()).unwrap();
+        let cells: Vec<_> = game.iter_cells().collect();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[3], ((0, 1), AnyTile::Open(1)));
+    }
+
+    #[test]
+    fn degenerate_boards() {
+        assert!(matches!(
+            Minefield::from_mine_coords((0, 3), &[]),
+            Err(GameError::InvalidBoardShape)
+        ));
+        assert!(matches!(
+            Minefield::from_binary_rows(&["11", "11"]),
+            Err(GameError::TooManyMines)
+        ));
+
+        let mut game = Game::new(Minefield::from_mine_coords((3, 2), &[]).unwrap());
+        assert_eq!(game.open((1, 1), now()).unwrap(), OpenOutcome::Win);
+        assert_eq!(game.cur_state(), GameState::InstantWin);
+    }
+
+    #[test]
+    fn chord_records_every_exploded_mine() {
+        let mut game = Game::new(field(&["*.*", "...", "..."]));
+        game.open((1, 1), now()).unwrap();
+        assert_eq!(game.tile_at((1, 1)), AnyTile::Open(2));
+        game.flag((1, 0)).unwrap();
+        game.flag((0, 1)).unwrap();
+        assert_eq!(
+            game.chord_open((1, 1), now()).unwrap(),
+            OpenOutcome::Explode
+        );
+        assert_eq!(game.exploded_mines(), &[(0, 0), (2, 0)]);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Exploded);
+        assert_eq!(game.tile_at((2, 0)), AnyTile::Exploded);
+        assert_eq!(game.cur_state(), GameState::Lose);
+
+        game.restart();
+        assert!(game.exploded_mines().is_empty());
+        game.open((0, 2), now()).unwrap();
+        game.open((2, 0), now()).unwrap();
+        assert_eq!(game.exploded_mines(), &[(2, 0)]);
+    }
+
+    #[test]
+    fn no_flood_fill_opens_single_tile() {
+        let mut game = Game::new(field(&["*...", "....", "...."]));
+        game.set_flood_fill(false);
+        assert_eq!(game.open((3, 2), now()).unwrap(), OpenOutcome::Safe);
+        assert_eq!(game.tile_at((3, 2)), AnyTile::Open(0));
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Closed);
+        assert_eq!(game.tile_at((2, 1)), AnyTile::Closed);
+        for coords in [
+            (1, 0),
+            (2, 0),
+            (3, 0),
+            (0, 1),
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (0, 2),
+            (1, 2),
+        ] {
+            assert_eq!(game.open(coords, now()).unwrap(), OpenOutcome::Safe);
+        }
+        assert_eq!(game.open((2, 2), now()).unwrap(), OpenOutcome::Win);
+        game.restart();
+        assert!(!game.is_flood_fill());
+    }
+
+    #[test]
+    fn open_with_depths_counts_flood_steps() {
+        let mut game = Game::new(field(&["....", "....", "....", "...*"]));
+        let (outcome, opened) = game.open_with_depths((0, 0), now()).unwrap();
+        assert_eq!(outcome, OpenOutcome::Win);
+        assert_eq!(opened[0], ((0, 0), 0));
+        assert_eq!(opened.len(), 15);
+        for ((x, y), depth) in opened {
+            assert_eq!(depth, u16::from(x.max(y)));
+        }
+
+        let mut game = Game::new(field(&["*...", "....", "....", "...."]));
+        let (outcome, opened) = game.open_with_depths((1, 0), now()).unwrap();
+        assert_eq!((outcome, opened), (OpenOutcome::Safe, vec![((1, 0), 0)]));
+        let (outcome, opened) = game.open_with_depths((1, 0), now()).unwrap();
+        assert_eq!((outcome, opened.len()), (OpenOutcome::NoChange, 0));
+    }
+
+    #[test]
+    fn winning_flags_remaining_mines() {
+        let mut game = Game::new(field(&["*...", "....", "....", "...*"]));
+        game.open((2, 1), now()).unwrap();
+        assert!(game.cur_state().is_final());
+        assert_eq!(game.flag_count.0, game.total_mines());
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+
+        let mut game = Game::new(field(&["*...", "....", "....", "...*"]));
+        game.set_auto_flag_on_win(false);
+        game.open((2, 1), now()).unwrap();
+        assert_eq!(game.flag_count.0, 0);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+    }
+
+    #[test]
+    fn chord_open_detailed_reports_each_tile() {
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.open((1, 1), now()).unwrap();
+        // the wrong flag leaves the mine as a closed neighbor
+        game.flag((2, 2)).unwrap();
+        let (outcome, steps) = game.chord_open_detailed((1, 1), now()).unwrap();
+        assert_eq!(outcome, OpenOutcome::Explode);
+        assert_eq!(steps.last(), Some(&((0, 0), OpenOutcome::Explode)));
+        assert!(steps.contains(&((1, 0), OpenOutcome::Safe)));
+        assert!(steps[..steps.len() - 1]
+            .iter()
+            .all(|&(_, outcome)| outcome == OpenOutcome::Safe));
+    }
+
+    #[test]
+    fn safe_regions_split_by_wall() {
+        // diagonal neighbors still connect through a one tile wide diagonal
+        assert_eq!(
+            field(&["..*.", ".*..", "*...", "...."])
+                .safe_regions()
+                .len(),
+            1
+        );
+
+        let regions = field(&["..**", ".**.", "**..", "*..."]).safe_regions();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0], [(0, 0), (1, 0), (0, 1)]);
+        assert_eq!(regions[1][0], (3, 1));
+
+        let regions = field(&["..*...", "..*...", "..*..."]).safe_regions();
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions[0], [(0, 0), (1, 0), (0, 1), (1, 1), (0, 2), (1, 2)]);
+        assert_eq!(regions[1][0], (3, 0));
+    }
+
+    #[test]
+    fn render_codes_after_loss() {
+        let mut game = Game::new(field(&["*...", "....", "....", "...*"]));
+        assert_eq!(game.cell_code_at((0, 0)), 9);
+        game.open((1, 0), now()).unwrap();
+        game.flag((0, 3)).unwrap();
+        game.open((0, 0), now()).unwrap();
+        assert_eq!(game.cell_code_at((0, 0)), 12);
+        assert_eq!(game.cell_code_at((3, 3)), 13);
+        assert_eq!(game.cell_code_at((0, 3)), 14);
+        let codes = game.render_codes();
+        assert_eq!(codes[(0, 0)], 12);
+        assert_eq!(codes[(1, 0)], 1);
+    }
+
+    #[test]
+    fn solution_view_of_partial_game() {
+        let mut game = Game::new(field(&["*...", "....", "....", "...*"]));
+        game.open((1, 0), now()).unwrap();
+        game.flag((2, 2)).unwrap();
+        let before = game.clone();
+        let solution = game.solution_view();
+        assert_eq!(game, before);
+        assert_eq!(solution[(0, 0)], AnyTile::Flag);
+        assert_eq!(solution[(3, 3)], AnyTile::Flag);
+        assert_eq!(solution[(1, 0)], AnyTile::Open(1));
+        assert_eq!(solution[(2, 2)], AnyTile::Open(1));
+        let open = solution
+            .iter()
+            .filter(|tile| matches!(tile, AnyTile::Open(_)))
+            .count();
+        assert_eq!(open, 14);
+    }
+
+    #[test]
+    fn best_opening_picks_largest_region() {
+        let minefield = field(&["..*.....", "..*.....", "..*.....", "..*....."]);
+        assert_eq!(minefield.best_opening(), ((4, 0), 20));
+        assert_eq!(field(&["*.*", ".*.", "*.*"]).best_opening(), ((0, 0), 0));
+        assert_eq!(field(&["...", "...", "..."]).best_opening(), ((0, 0), 9));
+    }
+
+    #[test]
+    fn auto_open_near_stays_in_radius() {
+        let mut game = Game::new(field(&["*...*...*", "*...*...*", "*...*...*", "*...*...*"]));
+        game.open((1, 1), now()).unwrap();
+        game.open((7, 1), now()).unwrap();
+        for y in 0..3 {
+            game.flag((0, y)).unwrap();
+            game.flag((8, y)).unwrap();
+        }
+        game.auto_open_trivial_near(&TrivialSolver, (1, 1), 1, now())
+            .unwrap();
+        assert_ne!(game.tile_at((2, 1)), AnyTile::Closed);
+        assert_eq!(game.tile_at((6, 1)), AnyTile::Closed);
+        game.auto_open_trivial_near(&TrivialSolver, (6, 1), 1, now())
+            .unwrap();
+        assert_ne!(game.tile_at((6, 1)), AnyTile::Closed);
+        assert!(game
+            .auto_open_trivial_near(&TrivialSolver, (9, 0), 1, now())
+            .is_err());
+    }
+
+    #[test]
+    fn force_open_flagged_mine() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((2, 0), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        assert_eq!(game.mines_left(), 1);
+        assert_eq!(game.open((0, 0), now()).unwrap(), OpenOutcome::NoChange);
+        assert_eq!(
+            game.force_open((0, 0), now()).unwrap(),
+            OpenOutcome::Explode
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Exploded);
+        assert_eq!(game.mines_left(), 2);
+
+        // in lenient mode the mistake counts as found
+        let mut game = Game::new_lenient(field(&["*..", "...", "..*"]));
+        game.open((2, 0), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        assert_eq!(
+            game.force_open((0, 0), now()).unwrap(),
+            OpenOutcome::Explode
+        );
+        assert_eq!(game.mines_left(), 1);
+    }
+
+    #[test]
+    fn open_step_matches_open() {
+        let rows = [
+            "........*",
+            ".........",
+            "....*....",
+            ".........",
+            "*........",
+            ".........",
+        ];
+        for start in [(0, 0), (6, 5), (2, 2)] {
+            let mut full = Game::new(field(&rows));
+            let outcome = full.open(start, now()).unwrap();
+            let mut stepped = Game::new(field(&rows));
+            let (mut stepped_outcome, mut pending) = stepped.open_step(start, 1, now()).unwrap();
+            assert!(pending);
+            while pending {
+                let (outcome, more) = stepped.open_step(start, 1, now()).unwrap();
+                if outcome != OpenOutcome::NoChange {
+                    stepped_outcome = outcome;
+                }
+                pending = more;
+            }
+            assert_eq!(stepped_outcome, outcome);
+            assert_eq!(full.render_codes(), stepped.render_codes());
+            assert_eq!(full.cur_state(), stepped.cur_state());
+        }
+
+        let mut game = Game::new(field(&rows));
+        assert_eq!(
+            game.open_step((0, 0), 0, now()).unwrap(),
+            (OpenOutcome::Safe, true)
+        );
+        assert_eq!(game.tile_at((1, 0)), AnyTile::Closed);
+    }
+
+    #[test]
+    fn flag_proven_mines_only_flags_proven() {
+        let mut game = Game::new(field(&["*.*", "...", "...", "..."]));
+        game.open((1, 3), now()).unwrap();
+        let solver = TrivialSolver.then(SubsetSolver);
+        assert_eq!(
+            game.flag_proven_mines(&solver).unwrap(),
+            FlagOutcome::MarkChanged
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+        assert_eq!(game.tile_at((2, 0)), AnyTile::Flag);
+        assert_eq!(game.tile_at((1, 0)), AnyTile::Closed);
+        assert_eq!(game.mines_left(), 0);
+        assert_eq!(
+            game.flag_proven_mines(&solver).unwrap(),
+            FlagOutcome::NoChange
+        );
+
+        // a 50/50 is left alone
+        let mut game = Game::new(field(&["*.", "..", ".."]));
+        game.open((0, 2), now()).unwrap();
+        assert_eq!(
+            game.flag_proven_mines(&solver).unwrap(),
+            FlagOutcome::NoChange
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+    }
+
+    #[test]
+    fn proven_mines_respect_the_flag_limit() {
+        // (0, 0) is walled in by the three mines, the solver knows nothing about it
+        let solver = TrivialSolver.then(SubsetSolver);
+        let start = || {
+            let mut game = Game::new(field(&[".*....", "**....", "......"]));
+            game.set_max_flags_enforced(true);
+            game.open((5, 2), now()).unwrap();
+            game.flag((0, 0)).unwrap();
+            assert_eq!(game.mines_left(), 2);
+            game
+        };
+        let flags = |game: &Game| {
+            [(1, 0), (0, 1), (1, 1)]
+                .into_iter()
+                .filter(|&pos| game.tile_at(pos) == AnyTile::Flag)
+                .count()
+        };
+
+        let mut game = start();
+        assert_eq!(
+            game.flag_proven_mines(&solver).unwrap(),
+            FlagOutcome::MarkChanged
+        );
+        assert_eq!((game.mines_left(), flags(&game)), (0, 2));
+
+        let mut game = start();
+        game.auto_play(&solver, 10, now()).unwrap();
+        assert_eq!((game.mines_left(), flags(&game)), (0, 2));
+    }
+
+    #[test]
+    fn lenient_game_stays_winnable() {
+        let rows = ["*..", "...", "..*"];
+        let mut game = Game::new_lenient(field(&rows));
+        assert!(game.is_winnable() && game.is_winnable_without_mistakes());
+        game.open((2, 0), now()).unwrap();
+        game.open((0, 0), now()).unwrap();
+        assert!(game.is_winnable());
+        assert!(!game.is_winnable_without_mistakes());
+        // (0, 2) is a zero and floods (1, 2)
+        for coords in [(0, 1), (0, 2)] {
+            game.open(coords, now()).unwrap();
+        }
+        assert!(game.ended());
+        assert!(!game.is_winnable());
+
+        let mut game = Game::new(field(&rows));
+        game.open((2, 0), now()).unwrap();
+        game.open((0, 0), now()).unwrap();
+        assert!(!game.is_winnable() && !game.is_winnable_without_mistakes());
+    }
+
+    #[test]
+    fn counter_values_with_flags() {
+        let mut game = Game::new(field(&["*.*", "...", "..*"]));
+        game.open((0, 2), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        game.flag((2, 0)).unwrap();
+        assert_eq!(game.total_mines(), 3);
+        assert_eq!(game.flag_count(), 2);
+        assert_eq!(game.mines_left(), 1);
+    }
+
+    #[test]
+    fn unflag_only_removes_flags() {
+        let mut game = Game::new(field(&["*.*", "...", "..*"]));
+        game.open((0, 2), now()).unwrap();
+        assert_eq!(game.unflag((0, 0)).unwrap(), FlagOutcome::NoChange);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+        game.flag((0, 0)).unwrap();
+        assert_eq!(game.flag_count(), 1);
+        assert_eq!(game.unflag((0, 0)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+        assert_eq!(game.flag_count(), 0);
+        game.flag_question((2, 0)).unwrap();
+        game.flag_question((2, 0)).unwrap();
+        assert_eq!(game.unflag((2, 0)).unwrap(), FlagOutcome::NoChange);
+        assert_eq!(game.tile_at((2, 0)), AnyTile::Question);
+        assert!(game.unflag((3, 0)).is_err());
+    }
+
+    #[test]
+    fn neighbor_count_by_position() {
+        let minefield = field(&[".....", ".....", "..*..", ".....", "....."]);
+        for coords in [(0, 0), (4, 0), (0, 4), (4, 4)] {
+            assert_eq!(minefield.neighbor_count(coords), 3);
+            assert!(minefield.is_corner(coords) && !minefield.is_edge(coords));
+        }
+        assert_eq!(minefield.neighbor_count((2, 0)), 5);
+        assert!(minefield.is_edge((2, 0)) && minefield.is_edge((0, 3)));
+        assert_eq!(minefield.neighbor_count((2, 2)), 8);
+        assert!(!minefield.is_corner((2, 2)) && !minefield.is_edge((2, 2)));
+
+        let row = field(&["...*"]);
+        assert_eq!(row.neighbor_count((0, 0)), 1);
+        assert_eq!(row.neighbor_count((1, 0)), 2);
+    }
+
+    #[test]
+    fn report_of_won_game() {
+        let minefield = field(&["*..", "...", "..."]);
+        let mut game = Game::new(minefield.clone());
+        assert_eq!(game.open((2, 2), now()).unwrap(), OpenOutcome::Win);
+        let report = game.report(now());
+        assert_eq!(report.state, GameState::InstantWin);
+        assert_eq!(report.opened, minefield.safe_count());
+        assert_eq!(report.config, GameConfig::new((3, 3), 1));
+        assert_eq!(report.flags, 1);
+        assert_eq!(report.bbbv, 1);
+    }
+
+    #[test]
+    fn neighbor_cells_of_center() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((1, 1), now()).unwrap();
+        game.open((2, 0), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        let cells: Vec<_> = game.iter_neighbor_cells((1, 1)).collect();
+        assert_eq!(cells.len(), 8);
+        assert!(cells.contains(&((0, 0), AnyTile::Flag)));
+        assert!(cells.contains(&((2, 0), AnyTile::Open(0))));
+        assert!(cells.contains(&((1, 0), AnyTile::Open(1))));
+        assert!(cells.contains(&((2, 2), AnyTile::Closed)));
+        assert_eq!(game.iter_neighbor_cells((0, 0)).count(), 3);
+    }
+
+    #[test]
+    fn saved_game_keeps_timing() {
+        let start = now();
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((2, 0), start).unwrap();
+        game.flag((0, 0)).unwrap();
+        game.validate().unwrap();
+
+        let json = serde_json::to_string(&game).unwrap();
+        let loaded: Game = serde_json::from_str(&json).unwrap();
+        loaded.validate().unwrap();
+        assert_eq!(loaded, game);
+        assert_eq!(loaded.started_at, Some(start));
+        assert_eq!(
+            loaded.elapsed_secs(start + chrono::TimeDelta::seconds(5)),
+            5
+        );
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_progress() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((2, 0), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+
+        let mut bad = game.clone();
+        bad.started_at = None;
+        assert!(matches!(
+            bad.validate(),
+            Err(GameError::InconsistentProgress)
+        ));
+        let mut bad = game.clone();
+        bad.open_count += 1;
+        assert!(matches!(
+            bad.validate(),
+            Err(GameError::InconsistentProgress)
+        ));
+        let mut bad = game.clone();
+        bad.flag_count -= 1;
+        assert!(matches!(
+            bad.validate(),
+            Err(GameError::InconsistentProgress)
+        ));
+
+        let mut lost = Game::new(field(&["*..", "...", "..*"]));
+        lost.open((0, 0), now()).unwrap();
+        assert_eq!(lost.cur_state(), GameState::InstantLoss);
+        lost.validate().unwrap();
+    }
+
+    #[test]
+    fn oversized_boards_are_rejected() {
+        let wide = String::from("1") + &"0".repeat(299);
+        assert!(matches!(
+            Minefield::from_binary_rows(&[&wide, &wide]),
+            Err(GameError::InvalidBoardShape)
+        ));
+        let tall = ["01"; 300];
+        assert!(matches!(
+            Minefield::from_binary_rows(&tall),
+            Err(GameError::InvalidBoardShape)
+        ));
+        let grid: Array2<AnyTile> = Array2::default((300, 2));
+        assert!(matches!(
+            Observation::new(grid, 1),
+            Err(GameError::InvalidBoardShape)
+        ));
+        let grid: Array2<AnyTile> = Array2::default((0, 2));
+        assert!(matches!(
+            Observation::new(grid, 1),
+            Err(GameError::InvalidBoardShape)
+        ));
+    }
+
+    #[test]
+    fn faster_wins_score_higher() {
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.open((1, 0), now()).unwrap();
+        let win_after = |secs| {
+            let mut game = game.clone();
+            let end = now() + chrono::TimeDelta::seconds(secs);
+            assert_eq!(game.open((2, 2), end).unwrap(), OpenOutcome::Win);
+            game.report(end).score()
+        };
+        let (fast, slow) = (win_after(10), win_after(20));
+        assert!(fast > slow && slow > 0);
+
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.open((0, 0), now()).unwrap();
+        assert_eq!(game.report(now()).score(), 0);
+    }
+
+    #[test]
+    fn pre_flagged_game() {
+        let minefield = field(&["*..", "...", "*.*"]);
+        let game = Game::with_flags(minefield.clone(), &[(0, 0), (2, 2), (0, 0)], true).unwrap();
+        assert_eq!(game.mines_left(), 1);
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Flag);
+        game.validate().unwrap();
+
+        assert!(matches!(
+            Game::with_flags(minefield.clone(), &[(1, 1)], true),
+            Err(GameError::FlagOnSafeTile)
+        ));
+        assert_eq!(
+            Game::with_flags(minefield.clone(), &[(1, 1)], false)
+                .unwrap()
+                .mines_left(),
+            2
+        );
+        assert!(Game::with_flags(minefield, &[(3, 0)], false).is_err());
+    }
+
+    #[test]
+    fn pre_placed_flags_change_before_the_first_move() {
+        let minefield = field(&["*..", "...", "*.*"]);
+        let mut game =
+            Game::with_flags(minefield.clone(), &[(0, 0), (1, 1), (2, 2)], false).unwrap();
+        assert_eq!(game.unflag((0, 0)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(
+            game.flag_question((2, 2)).unwrap(),
+            FlagOutcome::MarkChanged
+        );
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Question);
+        assert_eq!(game.mines_left(), 2);
+        assert_eq!(game.cur_state(), GameState::NotStarted);
+        assert_ne!(
+            game.force_open((1, 1), now()).unwrap(),
+            OpenOutcome::NoChange
+        );
+        assert_eq!(game.cur_state(), GameState::InProgress);
+
+        let mut game = Game::with_flags(minefield, &[(0, 0)], true).unwrap();
+        assert_eq!(game.clear_flags().unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.mines_left(), 3);
+    }
+
+    #[test]
+    fn density_round_trips() {
+        assert!((GameConfig::BEGINNER.mine_density() - 0.123).abs() < 0.001);
+        assert!((GameConfig::EXPERT.mine_density() - 0.206).abs() < 0.001);
+        for config in [
+            GameConfig::BEGINNER,
+            GameConfig::INTERMEDIATE,
+            GameConfig::EXPERT,
+            GameConfig::EVIL,
+        ] {
+            assert_eq!(
+                GameConfig::from_density(config.size, config.mine_density()),
+                config
+            );
+        }
+        assert_eq!(GameConfig::from_density((9, 9), 0.2).mines, 16);
+        assert_eq!(GameConfig::from_density((9, 9), 0.0).mines, 1);
+        assert_eq!(GameConfig::from_density((9, 9), 1.0).mines, 80);
+        assert_eq!(GameConfig::from_density((9, 9), f32::NAN).mines, 1);
+    }
+
+    #[test]
+    fn chord_over_question_marks() {
+        let make = |ignore_questions| {
+            let mut game = Game::new(field(&["*..", "...", "..."]));
+            game.set_chord_ignores_questions(ignore_questions);
+            game.open((1, 1), now()).unwrap();
+            game.flag((0, 0)).unwrap();
+            game.flag_question((2, 2)).unwrap();
+            game.flag_question((2, 2)).unwrap();
+            assert_eq!(game.tile_at((2, 2)), AnyTile::Question);
+            game
+        };
+
+        let mut game = make(false);
+        assert!(!game.is_chordable((1, 1)));
+        assert_eq!(
+            game.chord_open((1, 1), now()).unwrap(),
+            OpenOutcome::NoChange
+        );
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Question);
+
+        let mut game = make(true);
+        assert!(game.is_chordable((1, 1)));
+        assert_eq!(game.chordable_cells(), vec![(1, 1)]);
+        assert_eq!(game.chord_open((1, 1), now()).unwrap(), OpenOutcome::Win);
+    }
+
+    #[test]
+    fn chord_over_questioned_mine_explodes() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.set_chord_ignores_questions(true);
+        game.open((1, 1), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        game.flag((2, 0)).unwrap();
+        game.flag_question((2, 2)).unwrap();
+        game.flag_question((2, 2)).unwrap();
+        assert_eq!(
+            game.chord_open((1, 1), now()).unwrap(),
+            OpenOutcome::Explode
+        );
+        assert_eq!(game.cur_state(), GameState::Lose);
+    }
+
+    #[test]
+    fn chord_without_flags() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.set_flood_fill(false);
+        for pos in [(1, 0), (2, 0), (2, 1), (1, 1), (0, 1)] {
+            game.open(pos, now()).unwrap();
+        }
+        assert_eq!(
+            game.chord_open((0, 1), now()).unwrap(),
+            OpenOutcome::NoChange
+        );
+
+        assert_eq!(
+            game.chord_open_deduced(&solver, (0, 1), now()).unwrap(),
+            OpenOutcome::Safe
+        );
+        assert_eq!(game.flag_count(), 0);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+        assert!(matches!(game.tile_at((0, 2)), AnyTile::Open(_)));
+        assert!(matches!(game.tile_at((1, 2)), AnyTile::Open(_)));
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Closed);
+        assert_eq!(
+            game.chord_open_deduced(&solver, (2, 1), now()).unwrap(),
+            OpenOutcome::Win
+        );
+    }
+
+    #[test]
+    fn config_validity() {
+        assert!(GameConfig::EXPERT.is_valid());
+        assert!(!GameConfig::new_unchecked((5, 5), 30).is_valid());
+        assert!(!GameConfig::new_unchecked((0, 5), 1).is_valid());
+        assert!(matches!(
+            GameConfig::try_new((5, 5), 30),
+            Err(GameError::TooManyMines)
+        ));
+        assert!(matches!(
+            GameConfig::try_new((5, 5), 25),
+            Err(GameError::TooManyMines)
+        ));
+        assert!(GameConfig::new_unchecked((5, 5), 24).is_valid());
+        assert_eq!(GameConfig::new((5, 5), 30).mines, 24);
+        assert_eq!(GameConfig::new((1, 1), 1).mines, 0);
+        assert!(GameConfig::new((1, 1), 1).is_valid());
+    }
+
+    #[test]
+    fn corner_one_flags_its_last_neighbor() {
+        let mut game = Game::new(field(&["*.", "..", "..", ".*"]));
+        game.open((1, 1), now()).unwrap();
+        game.open((1, 0), now()).unwrap();
+        game.open((0, 1), now()).unwrap();
+        assert_eq!(game.flaggable_cells(), vec![(0, 0)]);
+        game.flag((0, 0)).unwrap();
+        assert!(game.flaggable_cells().is_empty());
+    }
+
+    #[test]
+    fn resolve_obvious_flags_or_opens() {
+        // a corner 1 whose only hidden neighbor is the mine
+        let mut game = Game::new(field(&["*.", "..", "..", ".*"]));
+        game.open((1, 0), now()).unwrap();
+        game.open((0, 1), now()).unwrap();
+        game.open((1, 1), now()).unwrap();
+        assert_eq!(
+            game.resolve_obvious((1, 0), now()).unwrap(),
+            (OpenOutcome::NoChange, FlagOutcome::MarkChanged)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+        assert_eq!(
+            game.resolve_obvious((1, 0), now()).unwrap(),
+            (OpenOutcome::NoChange, FlagOutcome::NoChange)
+        );
+
+        // a 1 whose mine is already flagged, the one hidden neighbor left is safe
+        let mut game = Game::new(field(&["*.", "..", "..", ".*"]));
+        game.open((1, 0), now()).unwrap();
+        game.open((1, 1), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        game.flag_question((0, 1)).unwrap();
+        game.flag_question((0, 1)).unwrap();
+        assert_eq!(
+            game.resolve_obvious((1, 0), now()).unwrap(),
+            (OpenOutcome::Safe, FlagOutcome::NoChange)
+        );
+        assert_eq!(game.tile_at((0, 1)), AnyTile::Open(1));
+        // (1, 1) still has two hidden neighbors
+        assert_eq!(
+            game.resolve_obvious((1, 1), now()).unwrap(),
+            (OpenOutcome::NoChange, FlagOutcome::NoChange)
+        );
+
+        // no flag left to place
+        let mut game = Game::new(field(&["*.", "..", "..", ".*"]));
+        game.set_max_flags_enforced(true);
+        for pos in [(1, 0), (0, 1), (1, 1)] {
+            game.open(pos, now()).unwrap();
+        }
+        game.flag((0, 3)).unwrap();
+        game.flag((1, 3)).unwrap();
+        assert_eq!(
+            game.resolve_obvious((1, 0), now()).unwrap(),
+            (OpenOutcome::NoChange, FlagOutcome::NoChange)
+        );
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+    }
+
+    #[test]
+    fn tile_predicates() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((0, 2), now()).unwrap();
+        game.flag((2, 2)).unwrap();
+
+        assert_eq!(game.count_at((0, 2)), Some(0));
+        assert!(
+            game.is_open_at((0, 2)) && !game.is_flagged_at((0, 2)) && !game.is_closed_at((0, 2))
+        );
+        assert_eq!(game.count_at((1, 1)), Some(2));
+
+        assert_eq!(game.count_at((2, 2)), None);
+        assert!(
+            !game.is_open_at((2, 2)) && game.is_flagged_at((2, 2)) && !game.is_closed_at((2, 2))
+        );
+
+        assert_eq!(game.count_at((0, 0)), None);
+        assert!(
+            !game.is_open_at((0, 0)) && !game.is_flagged_at((0, 0)) && game.is_closed_at((0, 0))
+        );
+    }
+
+    #[test]
+    fn mines_revealed_from_the_exploded_one() {
+        let mut game = Game::new(field(&["*...*", ".....", "..*..", ".....", "*...*"]));
+        assert_eq!(
+            game.mine_reveal_order(),
+            vec![(0, 0), (4, 0), (2, 2), (0, 4), (4, 4)]
+        );
+        game.open((0, 2), now()).unwrap();
+        assert_eq!(game.open((4, 4), now()).unwrap(), OpenOutcome::Explode);
+        assert_eq!(
+            game.mine_reveal_order(),
+            vec![(4, 4), (2, 2), (4, 0), (0, 4), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn chord_blocked_until_question_cleared() {
+        let mut game = Game::new(field(&["*..", "...", "..."]));
+        game.open((1, 1), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        game.flag_question((2, 2)).unwrap();
+        game.flag_question((2, 2)).unwrap();
+        assert_eq!(
+            game.chord_open((1, 1), now()).unwrap(),
+            OpenOutcome::NoChange
+        );
+        assert_eq!(game.tile_at((2, 0)), AnyTile::Closed);
+
+        game.clear_questions().unwrap();
+        assert!(game.is_chordable((1, 1)));
+        assert_eq!(game.chord_open((1, 1), now()).unwrap(), OpenOutcome::Win);
+    }
+
+    #[test]
+    fn elapsed_millis() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        assert_eq!(game.elapsed_millis(now()), 0);
+        game.open((1, 0), now()).unwrap();
+        let later = now() + chrono::TimeDelta::milliseconds(1234);
+        assert_eq!(game.elapsed_millis(later), 1234);
+        assert_eq!(game.elapsed_secs(later), 1);
+    }
+
+    #[test]
+    fn thumbnail_extremes() {
+        let full = field(&["********"; 4]);
+        let thumbnail = full.to_thumbnail(4);
+        assert_eq!(thumbnail.shape(), [4, 2]);
+        assert!(thumbnail.iter().all(|&pixel| pixel == 255));
+
+        let empty = field(&["......"; 3]);
+        let thumbnail = empty.to_thumbnail(4);
+        assert_eq!(thumbnail.shape(), [3, 2]);
+        assert!(thumbnail.iter().all(|&pixel| pixel == 0));
+        assert_eq!(empty.to_thumbnail(100).shape(), [6, 3]);
+        assert_eq!(empty.to_thumbnail(0).shape(), [1, 1]);
+
+        assert_eq!(field(&["*.", ".*"]).to_thumbnail(1)[[0, 0]], 128);
+    }
+
+    #[test]
+    fn chord_flag_stops_at_the_mine_limit() {
+        // (1, 0) counts the two mines on the top row
+        let minefield = field(&["*.*", "...", "..*", "..."]);
+        let mut game = Game::new(minefield.clone());
+        game.set_max_flags_enforced(true);
+        for pos in [(1, 0), (0, 1), (1, 1), (2, 1)] {
+            game.open(pos, now()).unwrap();
+        }
+        game.flag((0, 3)).unwrap();
+        game.flag((1, 3)).unwrap();
+        assert_eq!(game.mines_left(), 1);
+        // the chord needs two flags with one left
+        assert_eq!(game.chord_flag((1, 0)).unwrap(), FlagOutcome::NoChange);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Closed);
+        assert_eq!(game.flag((0, 0)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.flag((2, 0)).unwrap(), FlagOutcome::NoChange);
+        assert_eq!(game.flag_question((2, 0)).unwrap(), FlagOutcome::NoChange);
+        // removing a flag is still allowed and makes room for the chord
+        assert_eq!(game.flag((1, 3)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.chord_flag((1, 0)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.tile_at((2, 0)), AnyTile::Flag);
+        assert_eq!(game.mines_left(), 0);
+
+        let mut game = Game::new(minefield);
+        for pos in [(1, 0), (0, 1), (1, 1), (2, 1)] {
+            game.open(pos, now()).unwrap();
+        }
+        game.flag((0, 3)).unwrap();
+        game.flag((1, 3)).unwrap();
+        assert_eq!(game.chord_flag((1, 0)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.mines_left(), -1);
+    }
+
+    #[test]
+    fn flood_region_matches_opening() {
+        let minefield = field(&["...*", "...*", "....", "*..."]);
+        let region = minefield.flood_region((0, 0));
+        let mut game = Game::new(minefield.clone());
+        game.open((0, 0), now()).unwrap();
+        let opened: Vec<Ix2> = iter_coords(minefield.size())
+            .filter(|&pos| game.is_open_at(pos))
+            .collect();
+        assert_eq!(region, opened);
+        assert_eq!(region.len(), minefield.opening_size((0, 0)));
+        // the zeros and the counts bordering them
+        assert!(region.contains(&(2, 1)) && region.contains(&(0, 2)) && !region.contains(&(0, 3)));
+
+        assert_eq!(minefield.flood_region((3, 0)), Vec::<Ix2>::new());
+        assert_eq!(minefield.flood_region((2, 0)), vec![(2, 0)]);
+    }
+
+    #[test]
+    fn recompute_counts_fixes_drift() {
+        let mut game = Game::new(field(&["*..", "...", "..*"]));
+        game.open((1, 0), now()).unwrap();
+        game.open((0, 1), now()).unwrap();
+        game.flag((0, 0)).unwrap();
+        let mut saved = game.clone();
+        saved.open_count = Saturating(6);
+        saved.flag_count = Saturating(0);
+        assert!(matches!(
+            saved.validate(),
+            Err(GameError::InconsistentProgress)
+        ));
+        // with the drifted count the next open wrongly wins
+        assert_eq!(saved.clone().open((1, 1), now()).unwrap(), OpenOutcome::Win);
+
+        assert!(saved.recompute_counts());
+        assert!(!saved.recompute_counts());
+        assert_eq!(saved, game);
+        saved.validate().unwrap();
+        assert_eq!(saved.open((1, 1), now()).unwrap(), OpenOutcome::Safe);
+        assert_eq!(saved.cur_state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn clue_radius_two() {
+        let minefield = field(&["*....", ".....", ".....", ".....", "....*"]);
+        assert_eq!(minefield.region_mine_count((2, 2), 2), 2);
+        assert_eq!(minefield.region_mine_count((2, 2), 1), 0);
+        assert_eq!(
+            minefield.region_mine_count((1, 1), 1),
+            minefield.get_count((1, 1))
+        );
+        assert_eq!(minefield.region_mine_count((3, 2), 2), 1);
+        assert_eq!(minefield.region_mine_count((0, 0), 9), 1);
+
+        let mut game = Game::new(minefield);
+        game.set_clue_radius(2);
+        game.validate().unwrap();
+        assert_eq!(game.open((2, 2), now()).unwrap(), OpenOutcome::Safe);
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Open(2));
+    }
+
+    #[test]
+    fn clue_radius_two_flood() {
+        let mut game = Game::new(field(&[
+            "*......", ".......", ".......", ".......", ".......",
+        ]));
+        game.set_clue_radius(2);
+        game.open((6, 4), now()).unwrap();
+        assert_eq!(game.tile_at((6, 4)), AnyTile::Open(0));
+        assert_eq!(game.tile_at((3, 3)), AnyTile::Open(0));
+        assert_eq!(game.tile_at((2, 2)), AnyTile::Open(1));
+        assert_eq!(game.tile_at((1, 1)), AnyTile::Closed);
+        // zeros reach the closed corner with a radius of 2
+        assert!(game.chordable_cells().contains(&(3, 3)));
+        assert_eq!(game.chord_flag((2, 2)).unwrap(), FlagOutcome::NoChange);
+
+        let mut invalid = game.clone();
+        invalid.rules.clue_radius = 0;
+        assert!(matches!(
+            invalid.validate(),
+            Err(GameError::InvalidClueRadius)
+        ));
+        game.set_clue_radius(0);
+        assert_eq!(game.clue_radius(), 1);
+    }
+
+    #[test]
+    fn clue_radius_two_chords() {
+        let mut game = Game::new(field(&[
+            "*.....*", ".......", ".......", ".......", ".......",
+        ]));
+        game.set_clue_radius(2);
+        game.open((3, 4), now()).unwrap();
+        for coords in [(1, 0), (0, 1), (1, 1)] {
+            assert_eq!(game.open(coords, now()).unwrap(), OpenOutcome::Safe);
+        }
+        assert_eq!(game.neighbor_summary((2, 2)).unopened(), 1);
+        assert_eq!(game.flaggable_cells(), vec![(0, 0)]);
+        assert_eq!(game.chord_flag((2, 2)).unwrap(), FlagOutcome::MarkChanged);
+        assert_eq!(game.tile_at((0, 0)), AnyTile::Flag);
+
+        assert!(!game.is_chordable((4, 2)));
+        game.flag((6, 0)).unwrap();
+        assert!(game.is_chordable((4, 2)));
+        assert_eq!(game.chord_open((4, 2), now()).unwrap(), OpenOutcome::Win);
+        assert_eq!(game.tile_at((5, 0)), AnyTile::Open(1));
+    }
+
+    #[test]
+    fn clue_radius_two_skips_the_solvers() {
+        let mut game = Game::new(field(&["*....", ".....", ".....", ".....", "....."]));
+        game.set_clue_radius(2);
+        game.open((4, 4), now()).unwrap();
+        assert!(!game.ended());
+        let before = game.clone();
+        let solver = TrivialSolver.then(SubsetSolver);
+        assert_eq!(
+            game.auto_open_trivial(&solver, now()).unwrap(),
+            OpenOutcome::NoChange
+        );
+        assert_eq!(
+            game.auto_open_trivial_near(&solver, (4, 4), 4, now())
+                .unwrap(),
+            OpenOutcome::NoChange
+        );
+        assert_eq!(
+            game.flag_proven_mines(&solver).unwrap(),
+            FlagOutcome::NoChange
+        );
+        assert_eq!(game.hint_near(&solver, (4, 4)), None);
+        assert_eq!(
+            game.auto_play(&solver, 100, now()).unwrap(),
+            AutoPlayResult::Stuck
+        );
+        assert_eq!(game, before);
+    }
+
+    #[test]
+    fn auto_play_clears_solvable_boards() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let config = GameConfig::new((8, 8), 10);
+        let mut won = 0;
+        for seed in 0..50 {
+            let minefield =
+                RandomMinefieldGenerator::new(seed, (4, 4), StartTile::AlwaysZero).generate(config);
+            let mut game = Game::new(minefield);
+            game.open((4, 4), now()).unwrap();
+            if game.ended() {
+                continue;
+            }
+            match game.auto_play(&solver, 1000, now()).unwrap() {
+                AutoPlayResult::Won => {
+                    assert_eq!(game.cur_state(), GameState::Win);
+                    won += 1;
+                }
+                result => {
+                    assert_eq!(result, AutoPlayResult::Stuck);
+                    assert!(!game.ended());
                 }
             }
         }
+        assert!(won > 0);
+    }
+
+    #[test]
+    fn auto_play_stops_at_a_guess() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let mut game = Game::new(field(&["..*..", ".....", "*...*", ".....", "..*.."]));
+        game.open((2, 2), now()).unwrap();
+        assert_eq!(
+            game.clone().auto_play(&solver, 0, now()).unwrap(),
+            AutoPlayResult::StepLimit
+        );
+        assert_eq!(
+            game.auto_play(&solver, 1000, now()).unwrap(),
+            AutoPlayResult::Stuck
+        );
+
+        // a 50/50 between the top corners
+        let mut game = Game::new(field(&["*.", "..", ".."]));
+        game.open((0, 2), now()).unwrap();
+        assert_eq!(
+            game.auto_play(&solver, 10, now()).unwrap(),
+            AutoPlayResult::Stuck
+        );
+        assert!(!game.ended());
+    }
+
+    #[test]
+    fn instant_win_as_win() {
+        let mut game = Game::new(field(&["*."]));
+        game.open((1, 0), now()).unwrap();
+        assert_eq!(game.cur_state(), GameState::InstantWin);
+
+        let mut game = Game::new(field(&["*."]));
+        game.set_instant_win_as_win(true);
+        assert_eq!(game.open((1, 0), now()).unwrap(), OpenOutcome::Win);
+        assert_eq!(game.cur_state(), GameState::Win);
+        assert!(game.started_at.is_some() && game.ended_at.is_some());
+        game.validate().unwrap();
+        assert_eq!(game.report(now()).state, GameState::Win);
+
+        let mut game = Game::new(field(&["*."]));
+        game.set_instant_win_as_win(true);
+        game.open((0, 0), now()).unwrap();
+        assert_eq!(game.cur_state(), GameState::InstantLoss);
+    }
+
+    #[test]
+    fn mine_mask_counts_the_mines() {
+        let minefield = RandomMinefieldGenerator::new(5, (0, 0), StartTile::SimpleSafe)
+            .generate(GameConfig::EXPERT);
+        let mask = minefield.mine_mask();
+        assert_eq!(
+            mask.iter().filter(|&&mine| mine).count(),
+            usize::from(minefield.game_config().mines)
+        );
+        assert!(!mask[[0, 0]]);
+    }
+
+    #[test]
+    fn hint_near_prefers_the_closer_tile() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let mut game = Game::new(field(&[".......*", "........", "........"]));
+        game.set_flood_fill(false);
+        game.open((1, 1), now()).unwrap();
+        assert_eq!(game.hint_near(&solver, (0, 2)), Some((0, 2)));
+        assert_eq!(game.hint_near(&solver, (2, 2)), Some((2, 2)));
+        assert_eq!(game.hint_near(&solver, (5, 1)), Some((2, 0)));
+        assert_eq!(game.hint_near(&solver, (1, 1)), Some((0, 0)));
+    }
+
+    #[test]
+    fn hint_near_falls_back_to_the_safest_tile() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let mut game = Game::new(field(&["......", "......", "*....*", "......"]));
+        game.set_flood_fill(false);
+        game.open((1, 1), now()).unwrap();
+        let hint = game.hint_near(&solver, (5, 3)).unwrap();
+        assert!(Observation::from_game(&game).safest_tiles().contains(&hint));
+        game.open((0, 2), now()).unwrap();
+        assert_eq!(game.hint_near(&solver, (0, 0)), None);
     }
 }
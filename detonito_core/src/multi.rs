@@ -0,0 +1,166 @@
+use crate::*;
+
+/// Several games played side by side as one match, e.g. a race on twin boards.
+///
+/// The match is lost as soon as any board is lost and won once every board is won, either way it has ended and moves
+/// on the other boards are rejected with [`GameError::AlreadyEnded`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MultiGame {
+    games: Vec<Game>,
+}
+
+impl MultiGame {
+    pub fn new(games: Vec<Game>) -> Self {
+        Self { games }
+    }
+
+    pub fn games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// The game on board `index`, `None` if there's no such board
+    pub fn game(&self, index: usize) -> Option<&Game> {
+        self.games.get(index)
+    }
+
+    /// Board `index` if the match can still take moves and `coords` is on it, boards can have different sizes
+    fn playable_game(&mut self, index: usize, coords: Ix2) -> Result<&mut Game> {
+        if self.ended() {
+            return Err(GameError::AlreadyEnded);
+        }
+        let game = self
+            .games
+            .get_mut(index)
+            .ok_or(GameError::InvalidBoardIndex)?;
+        game.minefield.validate_coords(coords)?;
+        Ok(game)
+    }
+
+    /// Apply a move to board `index`, see [`Game::apply`]
+    pub fn apply(
+        &mut self,
+        index: usize,
+        action: Action,
+        now: DateTime<Utc>,
+    ) -> Result<ActionOutcome> {
+        self.playable_game(index, action.coords())?
+            .apply(action, now)
+    }
+
+    /// Open a tile on board `index`, see [`Game::open`]
+    pub fn open(&mut self, index: usize, coords: Ix2, now: DateTime<Utc>) -> Result<OpenOutcome> {
+        self.playable_game(index, coords)?.open(coords, now)
+    }
+
+    /// Flag a tile on board `index`, see [`Game::flag`]
+    pub fn flag(&mut self, index: usize, coords: Ix2) -> Result<FlagOutcome> {
+        self.playable_game(index, coords)?.flag(coords)
+    }
+
+    /// Every board was won, false without boards
+    pub fn all_won(&self) -> bool {
+        !self.games.is_empty()
+            && self
+                .games
+                .iter()
+                .all(|game| matches!(game.cur_state(), GameState::Win | GameState::InstantWin))
+    }
+
+    /// Any board was lost
+    pub fn any_lost(&self) -> bool {
+        self.games
+            .iter()
+            .any(|game| matches!(game.cur_state(), GameState::Lose | GameState::InstantLoss))
+    }
+
+    /// The match was won or lost, see [`MultiGame`]
+    pub fn ended(&self) -> bool {
+        self.all_won() || self.any_lost()
+    }
+
+    /// State of the match as a whole: lost or won as described in [`MultiGame`], otherwise in progress once any board
+    /// was started
+    pub fn cur_state(&self) -> GameState {
+        if self.any_lost() {
+            GameState::Lose
+        } else if self.all_won() {
+            GameState::Win
+        } else if self.games.iter().all(|game| game.cur_state().is_initial()) {
+            GameState::NotStarted
+        } else {
+            GameState::InProgress
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now};
+    use alloc::vec;
+
+    #[test]
+    fn winning_every_board_wins() {
+        let mut multi = MultiGame::new(vec![
+            Game::new(field(&["*..", "...", "..."])),
+            Game::new(field(&["...", "...", "..*"])),
+        ]);
+        assert_eq!(multi.cur_state(), GameState::NotStarted);
+        assert_eq!(multi.open(0, (2, 2), now()).unwrap(), OpenOutcome::Win);
+        assert!(!multi.all_won());
+        assert_eq!(multi.cur_state(), GameState::InProgress);
+        assert!(matches!(
+            multi.open(2, (0, 0), now()),
+            Err(GameError::InvalidBoardIndex)
+        ));
+        assert_eq!(multi.open(1, (0, 0), now()).unwrap(), OpenOutcome::Win);
+        assert!(multi.all_won() && !multi.any_lost() && multi.ended());
+        assert_eq!(multi.cur_state(), GameState::Win);
+        assert!(!MultiGame::new(Vec::new()).all_won());
+    }
+
+    #[test]
+    fn losing_one_board_loses() {
+        let mut multi = MultiGame::new(vec![
+            Game::new(field(&["*..", "...", "..."])),
+            Game::new(field(&["...", "...", "..*"])),
+        ]);
+        multi.open(0, (2, 2), now()).unwrap();
+        multi.open(1, (1, 1), now()).unwrap();
+        multi.flag(1, (2, 2)).unwrap();
+        assert_eq!(multi.open(1, (2, 2), now()).unwrap(), OpenOutcome::NoChange);
+        multi.flag(1, (2, 2)).unwrap();
+        assert_eq!(multi.open(1, (2, 2), now()).unwrap(), OpenOutcome::Explode);
+        assert!(multi.any_lost() && !multi.all_won());
+        assert_eq!(multi.cur_state(), GameState::Lose);
+        assert!(matches!(
+            multi.open(0, (0, 1), now()),
+            Err(GameError::AlreadyEnded)
+        ));
+    }
+
+    #[test]
+    fn out_of_bounds_moves_are_rejected() {
+        let mut multi = MultiGame::new(vec![
+            Game::new(field(&["*..", "...", "..."])),
+            Game::new(field(&["*....", "....."])),
+        ]);
+        assert!(matches!(
+            multi.open(0, (4, 0), now()),
+            Err(GameError::InvalidCoords)
+        ));
+        assert!(matches!(
+            multi.apply(1, Action::Open((0, 2)), now()),
+            Err(GameError::InvalidCoords)
+        ));
+        assert!(matches!(
+            multi.flag(1, (5, 1)),
+            Err(GameError::InvalidCoords)
+        ));
+        assert_eq!(multi.cur_state(), GameState::NotStarted);
+        assert_eq!(
+            multi.apply(1, Action::Open((4, 1)), now()).unwrap(),
+            ActionOutcome::Open(OpenOutcome::Safe)
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use crate::*;
+use alloc::boxed::Box;
+
+/// The moves of a won game with their times, enough to replay it from the minefield and check the result
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub minefield: Minefield,
+    /// In the order they were played
+    pub moves: Vec<(DateTime<Utc>, Action)>,
+    /// Claimed [`GameReport::elapsed_secs`]
+    pub elapsed_secs: u32,
+}
+
+impl Replay {
+    pub fn new(minefield: Minefield) -> Self {
+        Self {
+            minefield,
+            moves: Vec::new(),
+            elapsed_secs: 0,
+        }
+    }
+
+    /// Record a move, the claimed time is left as is
+    pub fn push(&mut self, action: Action, now: DateTime<Utc>) {
+        self.moves.push((now, action));
+    }
+
+    /// Play every move on a new game with the default rules and check it ends in a win in the claimed time, the
+    /// error is the first divergence found. A move can be a no-op but it can't be rejected, so moves after the game
+    /// ended fail.
+    pub fn verify(&self) -> Result<GameReport> {
+        self.minefield.validate()?;
+        let mut game = Game::new(self.minefield.clone());
+        let mut last = None;
+        for (index, &(now, action)) in self.moves.iter().enumerate() {
+            if last.is_some_and(|last| now < last) {
+                return Err(GameError::ReplayOutOfOrder(index));
+            }
+            last = Some(now);
+            game.apply(action, now)
+                .map_err(|source| GameError::ReplayMoveRejected {
+                    index,
+                    source: Box::new(source),
+                })?;
+        }
+        let Some(last) = last else {
+            return Err(GameError::ReplayNotWon);
+        };
+        let report = game.report(last);
+        if !matches!(report.state, GameState::Win | GameState::InstantWin) {
+            return Err(GameError::ReplayNotWon);
+        }
+        if report.elapsed_secs != self.elapsed_secs {
+            return Err(GameError::ReplayTimeMismatch {
+                claimed: self.elapsed_secs,
+                actual: report.elapsed_secs,
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now};
+    use chrono::TimeDelta;
+
+    fn winning_replay() -> Replay {
+        let mut replay = Replay::new(field(&["*..", "...", "..*"]));
+        replay.push(Action::Open((2, 0)), now());
+        replay.push(Action::Flag((0, 0)), now());
+        replay.push(Action::Open((0, 2)), now() + TimeDelta::seconds(7));
+        replay.elapsed_secs = 7;
+        replay
+    }
+
+    #[test]
+    fn winning_replay_verifies() {
+        let report = winning_replay().verify().unwrap();
+        assert_eq!(report.state, GameState::Win);
+        assert_eq!(report.elapsed_secs, 7);
+    }
+
+    #[test]
+    fn tampered_replay_fails() {
+        let mut replay = winning_replay();
+        replay.elapsed_secs = 3;
+        assert!(matches!(
+            replay.verify(),
+            Err(GameError::ReplayTimeMismatch {
+                claimed: 3,
+                actual: 7
+            })
+        ));
+
+        let mut replay = winning_replay();
+        replay.moves[1].1 = Action::Open((0, 0));
+        assert!(matches!(
+            replay.verify(),
+            Err(GameError::ReplayMoveRejected { index: 2, .. })
+        ));
+
+        let mut replay = winning_replay();
+        replay.moves[2].0 = now() - TimeDelta::seconds(1);
+        assert!(matches!(
+            replay.verify(),
+            Err(GameError::ReplayOutOfOrder(2))
+        ));
+
+        let mut replay = winning_replay();
+        replay.moves[0].1 = Action::Open((3, 0));
+        assert!(matches!(
+            replay.verify(),
+            Err(GameError::ReplayMoveRejected { index: 0, ref source })
+                if matches!(**source, GameError::InvalidCoords)
+        ));
+
+        let mut replay = winning_replay();
+        replay.moves[1].1 = Action::ChordFlag((0, 7));
+        assert!(matches!(
+            replay.verify(),
+            Err(GameError::ReplayMoveRejected { index: 1, .. })
+        ));
+
+        // the game is won after the last move, even a no-op afterwards is rejected
+        for action in [Action::Open((2, 0)), Action::ChordFlag((2, 0))] {
+            let mut replay = winning_replay();
+            replay.push(action, now() + TimeDelta::seconds(7));
+            assert!(matches!(
+                replay.verify(),
+                Err(GameError::ReplayMoveRejected { index: 3, ref source })
+                    if matches!(**source, GameError::AlreadyEnded)
+            ));
+        }
+
+        let mut replay = winning_replay();
+        replay.moves.pop();
+        assert!(matches!(replay.verify(), Err(GameError::ReplayNotWon)));
+        assert!(matches!(
+            Replay::new(replay.minefield).verify(),
+            Err(GameError::ReplayNotWon)
+        ));
+    }
+}
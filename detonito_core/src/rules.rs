@@ -0,0 +1,95 @@
+use crate::*;
+
+/// The rule variant a game is played with, see [`Game::with_rules`]. The default is classic minesweeper, each rule is
+/// described on its [`Game`] setter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RulesConfig {
+    /// Practice mode, opening a mine counts as a mistake instead of ending the game, see [`Game::set_lenient`]
+    pub lenient: bool,
+    /// Opening a zero opens its neighbors, without it every safe tile has to be opened by hand, see
+    /// [`Game::set_flood_fill`]
+    pub flood_fill: bool,
+    /// Winning flags every mine left unflagged, see [`Game::set_auto_flag_on_win`]
+    pub auto_flag_on_win: bool,
+    /// Chording treats question marks like closed tiles and opens them, see [`Game::set_chord_ignores_questions`]
+    pub chord_ignores_questions: bool,
+    /// Flagging never places more flags than there are mines left, see [`Game::set_max_flags_enforced`]
+    pub max_flags_enforced: bool,
+    /// How far open tiles count mines, 1 for the usual adjacent tiles, see [`Game::set_clue_radius`]
+    pub clue_radius: u8,
+    /// Winning on the first move ends in [`GameState::Win`] instead of [`GameState::InstantWin`], see
+    /// [`Game::set_instant_win_as_win`]
+    pub instant_win_as_win: bool,
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            lenient: false,
+            flood_fill: true,
+            auto_flag_on_win: true,
+            chord_ignores_questions: false,
+            max_flags_enforced: false,
+            clue_radius: 1,
+            instant_win_as_win: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now};
+
+    const ROWS: [&str; 4] = ["*...", "....", "...*", "*..."];
+
+    #[test]
+    fn default_rules_are_classic() {
+        let mut classic = Game::new(field(&ROWS));
+        let mut game = Game::with_rules(field(&ROWS), RulesConfig::default());
+        assert_eq!(game, classic);
+        for pos in [(2, 0), (1, 1), (3, 3), (0, 1)] {
+            assert_eq!(game.open(pos, now()).ok(), classic.open(pos, now()).ok());
+        }
+        assert_eq!(game, classic);
+        assert!(Game::new_lenient(field(&ROWS)).rules().lenient);
+    }
+
+    #[test]
+    fn custom_rules() {
+        let rules = RulesConfig {
+            flood_fill: false,
+            clue_radius: 20,
+            ..Default::default()
+        };
+        let mut game = Game::with_rules(field(&ROWS), rules);
+        assert_eq!(game.clue_radius(), Game::MAX_CLUE_RADIUS);
+        assert!(!game.is_flood_fill());
+        game.set_rules(RulesConfig {
+            flood_fill: false,
+            ..Default::default()
+        });
+        assert_eq!(game.clue_radius(), 1);
+        game.open((2, 0), now()).unwrap();
+        assert_eq!(game.open_count.0, 1);
+
+        let mut classic = Game::new(field(&ROWS));
+        classic.open((2, 0), now()).unwrap();
+        assert!(classic.open_count.0 > 1);
+    }
+
+    #[test]
+    fn saved_rules_keep_their_keys() {
+        let mut game = Game::new(field(&ROWS));
+        game.set_flood_fill(false);
+        let mut saved = serde_json::to_value(&game).unwrap();
+        assert_eq!(saved["flood_fill"], false);
+        assert_eq!(serde_json::from_value::<Game>(saved.clone()).unwrap(), game);
+
+        // saved before the rule existed
+        saved.as_object_mut().unwrap().remove("flood_fill");
+        let loaded: Game = serde_json::from_value(saved).unwrap();
+        assert_eq!(loaded.rules(), &RulesConfig::default());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::*;
+
+/// Totals of a [`simulate`] run
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimReport {
+    pub games: u32,
+    pub wins: u32,
+    /// Tiles opened without a deduction, the first move doesn't count
+    pub guesses: u32,
+    /// Sum of [`Minefield::bbbv`] over every board
+    pub bbbv: u32,
+}
+
+impl SimReport {
+    /// Fraction of games won, 0 when no game was played
+    pub fn win_rate(&self) -> f64 {
+        ratio(self.wins, self.games)
+    }
+
+    pub fn average_guesses(&self) -> f64 {
+        ratio(self.guesses, self.games)
+    }
+
+    pub fn average_bbbv(&self) -> f64 {
+        ratio(self.bbbv, self.games)
+    }
+}
+
+fn ratio(total: u32, games: u32) -> f64 {
+    if games == 0 {
+        0.0
+    } else {
+        f64::from(total) / f64::from(games)
+    }
+}
+
+/// Play one board per seed and add up the results, useful to compare solvers on the same boards.
+///
+/// Each board comes from [`RandomMinefieldGenerator`] with the first move in the center opening a zero. The game is
+/// played by [`Game::auto_play`], when it gets stuck [`Observation::best_guess_deterministic`] is opened, so a run only
+/// depends on the seeds and the solver.
+///
+/// ```
+/// use detonito_core::*;
+///
+/// let solver = TrivialSolver.then(SubsetSolver);
+/// let report = simulate(GameConfig::BEGINNER, 0..5, &solver);
+/// assert_eq!(report.games, 5);
+/// assert!(report.wins <= report.games);
+/// assert!(report.average_bbbv() > 0.0);
+/// ```
+pub fn simulate(
+    config: GameConfig,
+    seeds: impl Iterator<Item = u64>,
+    solver: &impl Solver,
+) -> SimReport {
+    let mut report = SimReport::default();
+    let start = (config.size.0 / 2, config.size.1 / 2);
+    for seed in seeds {
+        let minefield =
+            RandomMinefieldGenerator::new(seed, start, StartTile::AlwaysZero).generate(config);
+        report.games += 1;
+        report.bbbv += u32::from(minefield.bbbv());
+        let (won, guesses) = play(minefield, start, solver);
+        report.wins += u32::from(won);
+        report.guesses += guesses;
+    }
+    report
+}
+
+/// Whether the game was won and how many guesses it took
+fn play(minefield: Minefield, start: Ix2, solver: &impl Solver) -> (bool, u32) {
+    // the timestamps don't matter here
+    let now = DateTime::<Utc>::default();
+    let mut game = Game::new(minefield);
+    let mut guesses = 0;
+    let _ = game.open(start, now);
+    while let Ok(AutoPlayResult::Stuck) = game.auto_play(solver, usize::MAX, now) {
+        let Some(coords) = Observation::from_game(&game).best_guess_deterministic() else {
+            break;
+        };
+        guesses += 1;
+        let _ = game.open(coords, now);
+    }
+    (matches!(game.cur_state(), GameState::Win), guesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulation_is_reproducible() {
+        let solver = TrivialSolver.then(SubsetSolver);
+        let report = simulate(GameConfig::BEGINNER, 0..50, &solver);
+        assert_eq!(report, simulate(GameConfig::BEGINNER, 0..50, &solver));
+        assert_eq!(report.games, 50);
+        assert!(report.win_rate() > 0.7);
+        // the subset rule never does worse than single clues alone
+        assert!(simulate(GameConfig::BEGINNER, 0..50, &TrivialSolver).wins <= report.wins);
+    }
+}
@@ -0,0 +1,1237 @@
+use crate::*;
+use alloc::vec;
+
+/// Components with more variables than this are not enumerated, the number of assignments grows exponentially
+pub const ENUMERATION_LIMIT: usize = 24;
+
+/// What a player can see of a game: open counts, flags and closed tiles, plus the total mine count
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    grid: Array2<AnyTile>,
+    mines: Ax,
+}
+
+impl Observation {
+    /// Fails with [`GameError::InvalidBoardShape`] when an axis is empty or longer than [`Ix::MAX`]
+    pub fn new(grid: Array2<AnyTile>, mines: Ax) -> Result<Self> {
+        let (dim_x, dim_y) = grid.dim();
+        if dim_x == 0 || dim_y == 0 || dim_x > Ix::MAX.into() || dim_y > Ix::MAX.into() {
+            return Err(GameError::InvalidBoardShape);
+        }
+        Ok(Self { grid, mines })
+    }
+
+    pub fn from_game(game: &Game) -> Self {
+        Self {
+            grid: game.grid.clone(),
+            mines: game.total_mines(),
+        }
+    }
+
+    /// Copy the tiles in `changed` from `game` instead of building a new observation, e.g. the tiles returned by
+    /// [`Game::open_with_depths`] or [`Game::chord_open_detailed`] plus the ones flagged. Other tiles are left alone, a
+    /// win or a loss changes mines all over the board so rebuild with [`Observation::from_game`] then.
+    ///
+    /// Fails without changing anything when the game has a different size or a coordinate is out of bounds.
+    pub fn apply_changes(&mut self, game: &Game, changed: &[Ix2]) -> Result<()> {
+        if self.grid.dim() != game.grid.dim() {
+            return Err(GameError::InvalidBoardShape);
+        }
+        for &coords in changed {
+            game.minefield.validate_coords(coords)?;
+        }
+        for &coords in changed {
+            self.grid[coords.convert()] = game.grid[coords.convert()];
+        }
+        self.mines = game.total_mines();
+        Ok(())
+    }
+
+    pub fn size(&self) -> Ix2 {
+        shape_of(&self.grid)
+    }
+
+    /// Hash of the size, mine count and every tile, the same position always gives the same value so it can key a
+    /// cache of [`AnalysisResult`] that outlives the program
+    pub fn fingerprint(&self) -> u64 {
+        let (size_x, size_y) = self.size();
+        let header = [size_x, size_y].into_iter().chain(self.mines.to_le_bytes());
+        // the code caps counts at 8, a wider clue radius goes past that
+        let tiles = iter_coords(self.size()).flat_map(|coords| match self.tile_at(coords) {
+            AnyTile::Open(count) => [0, count],
+            tile => [tile.code(), 0],
+        });
+        fnv1a(header.chain(tiles))
+    }
+
+    pub fn total_mines(&self) -> Ax {
+        self.mines
+    }
+
+    pub fn tile_at(&self, coords: Ix2) -> AnyTile {
+        self.grid[coords.convert()]
+    }
+
+    /// Tiles that could still be a mine or not, question marks are just closed tiles to the solver
+    pub fn is_unknown(&self, coords: Ix2) -> bool {
+        matches!(self.tile_at(coords), AnyTile::Closed | AnyTile::Question)
+    }
+
+    /// Flags, and mines exploded in lenient mode
+    pub fn is_known_mine(&self, coords: Ix2) -> bool {
+        matches!(self.tile_at(coords), AnyTile::Flag | AnyTile::Exploded)
+    }
+}
+
+/// Where an equation comes from
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum EquationKind {
+    /// The count of an open tile, minus its adjacent flags
+    Local(Ix2),
+    /// The total mine count, minus all flags
+    Global,
+}
+
+/// The sum of mines over some variables
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Equation {
+    pub kind: EquationKind,
+    pub variable_ids: Vec<usize>,
+    pub mines: Ax,
+}
+
+/// Every unknown tile is a variable that is either a mine or not, the equations constrain how many mines there are.
+///
+/// Built by [`build_constraints`] in a stable order: variables are numbered row-major, local equations come in the
+/// row-major order of their clue with the global equation last, and the `variable_ids` of every equation are sorted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintProblem {
+    pub variables: Vec<Ix2>,
+    pub equations: Vec<Equation>,
+}
+
+/// Clues that cannot be satisfied, usually caused by wrong flags
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Contradiction {
+    /// An open tile has more adjacent flags than its count, or fewer unknown neighbors than the mines left for it
+    LocalClueImpossible(Ix2),
+    /// There are more flags than mines, or fewer unknown tiles than unflagged mines
+    GlobalMineCountImpossible,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintBuildOutput {
+    pub problem: ConstraintProblem,
+    pub contradictions: Vec<Contradiction>,
+}
+
+/// Variables linked by local equations, they can be enumerated independently from the rest
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintComponent {
+    pub variable_ids: Vec<usize>,
+    pub equation_ids: Vec<usize>,
+}
+
+/// How [`build_constraints_with`] treats the player's flags, exploded mines are always mines
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum FlagSemantics {
+    /// Flags are mines, wrong flags show up as contradictions
+    #[default]
+    Strict,
+    /// Flags are unknown tiles like any closed one
+    Ignore,
+}
+
+/// Build the equations for an observation, flags and exploded mines are trusted to be mines, impossible clues are
+/// reported and left out, see [`ConstraintProblem`] for the order
+///
+/// ```
+/// use detonito_core::*;
+///
+/// let minefield = Minefield::from_mine_coords((4, 4), &[(0, 0), (3, 3)]).unwrap();
+/// let mut game = Game::new(minefield);
+/// game.open((2, 1), Default::default()).unwrap();
+/// let ConstraintBuildOutput { problem, .. } = build_constraints(&Observation::from_game(&game));
+/// assert!(problem.equations.iter().all(|eq| eq.variable_ids.is_sorted()));
+/// assert_eq!(problem.equations.last().map(|eq| eq.kind), Some(EquationKind::Global));
+/// ```
+pub fn build_constraints(obs: &Observation) -> ConstraintBuildOutput {
+    build_constraints_with(obs, FlagSemantics::Strict)
+}
+
+/// Same as [`build_constraints`] with a choice of how flags are treated
+pub fn build_constraints_with(
+    obs: &Observation,
+    semantics: FlagSemantics,
+) -> ConstraintBuildOutput {
+    let is_ignored_flag =
+        |coords| semantics == FlagSemantics::Ignore && obs.tile_at(coords) == AnyTile::Flag;
+    let is_unknown = |coords| obs.is_unknown(coords) || is_ignored_flag(coords);
+    let is_known_mine = |coords| obs.is_known_mine(coords) && !is_ignored_flag(coords);
+
+    let mut variable_ids: Array2<Option<usize>> = Array2::default(obs.grid.dim());
+    let mut variables = Vec::new();
+    let mut flags: Ax = 0;
+    for coords in iter_coords(obs.size()) {
+        if is_unknown(coords) {
+            variable_ids[coords.convert()] = Some(variables.len());
+            variables.push(coords);
+        } else if is_known_mine(coords) {
+            flags += 1;
+        }
+    }
+
+    let mut equations = Vec::new();
+    let mut contradictions = Vec::new();
+    for coords in iter_coords(obs.size()) {
+        let AnyTile::Open(count) = obs.tile_at(coords) else {
+            continue;
+        };
+        let mut adjacent_flags = 0;
+        let mut adjacent_ids = Vec::new();
+        // neighbors come top row first, like the variables, so the ids are already sorted
+        for pos in obs.grid.iter_adjacent(coords) {
+            if let Some(id) = variable_ids[pos.convert()] {
+                adjacent_ids.push(id);
+            } else if is_known_mine(pos) {
+                adjacent_flags += 1;
+            }
+        }
+        debug_assert!(adjacent_ids.is_sorted());
+        match count.checked_sub(adjacent_flags) {
+            Some(mines) if usize::from(mines) <= adjacent_ids.len() => {
+                if !adjacent_ids.is_empty() {
+                    equations.push(Equation {
+                        kind: EquationKind::Local(coords),
+                        variable_ids: adjacent_ids,
+                        mines: mines.into(),
+                    });
+                }
+            }
+            _ => contradictions.push(Contradiction::LocalClueImpossible(coords)),
+        }
+    }
+
+    match obs.mines.checked_sub(flags) {
+        Some(mines) if usize::from(mines) <= variables.len() => {
+            equations.push(Equation {
+                kind: EquationKind::Global,
+                variable_ids: (0..variables.len()).collect(),
+                mines,
+            });
+        }
+        _ => contradictions.push(Contradiction::GlobalMineCountImpossible),
+    }
+
+    ConstraintBuildOutput {
+        problem: ConstraintProblem {
+            variables,
+            equations,
+        },
+        contradictions,
+    }
+}
+
+/// Flags that contradict the clues on their own, because an adjacent open tile has more flags around it than its
+/// count or because there are more flags than mines, in row-major order. Unlike comparing against the minefield this
+/// only uses what the player can see.
+pub fn validate_flags(obs: &Observation) -> Vec<Ix2> {
+    let ConstraintBuildOutput { contradictions, .. } = build_constraints(obs);
+    let is_flag = |coords: &Ix2| obs.tile_at(*coords) == AnyTile::Flag;
+    let mut wrong = Vec::new();
+    for contradiction in contradictions {
+        match contradiction {
+            Contradiction::LocalClueImpossible(coords) => {
+                let AnyTile::Open(count) = obs.tile_at(coords) else {
+                    continue;
+                };
+                let adjacent_mines = obs
+                    .grid
+                    .iter_adjacent(coords)
+                    .filter(|&pos| obs.is_known_mine(pos))
+                    .count();
+                if adjacent_mines > count.into() {
+                    wrong.extend(obs.grid.iter_adjacent(coords).filter(is_flag));
+                }
+            }
+            Contradiction::GlobalMineCountImpossible => {
+                let known_mines = iter_coords(obs.size())
+                    .filter(|&coords| obs.is_known_mine(coords))
+                    .count();
+                if known_mines > obs.mines.into() {
+                    wrong.extend(iter_coords(obs.size()).filter(is_flag));
+                }
+            }
+        }
+    }
+    sort_row_major(&mut wrong);
+    wrong
+}
+
+impl ConstraintProblem {
+    /// Split the variables of local equations into independent groups, variables that aren't in any local equation
+    /// aren't part of any component
+    pub fn components(&self) -> Vec<ConstraintComponent> {
+        let mut component_of: Vec<Option<usize>> = vec![None; self.variables.len()];
+        let mut equations_of: Vec<Vec<usize>> = vec![Vec::new(); self.variables.len()];
+        for (equation_id, equation) in self.equations.iter().enumerate() {
+            if equation.kind == EquationKind::Global {
+                continue;
+            }
+            for &variable_id in &equation.variable_ids {
+                equations_of[variable_id].push(equation_id);
+            }
+        }
+
+        let mut components = Vec::new();
+        for start in 0..self.variables.len() {
+            if component_of[start].is_some() || equations_of[start].is_empty() {
+                continue;
+            }
+            let component_id = components.len();
+            let mut variable_ids = vec![start];
+            let mut equation_ids = Vec::new();
+            component_of[start] = Some(component_id);
+            let mut next = 0;
+            while let Some(&variable_id) = variable_ids.get(next) {
+                next += 1;
+                for &equation_id in &equations_of[variable_id] {
+                    if equation_ids.contains(&equation_id) {
+                        continue;
+                    }
+                    equation_ids.push(equation_id);
+                    for &other_id in &self.equations[equation_id].variable_ids {
+                        if component_of[other_id].is_none() {
+                            component_of[other_id] = Some(component_id);
+                            variable_ids.push(other_id);
+                        }
+                    }
+                }
+            }
+            variable_ids.sort_unstable();
+            equation_ids.sort_unstable();
+            components.push(ConstraintComponent {
+                variable_ids,
+                equation_ids,
+            });
+        }
+        components
+    }
+}
+
+/// Depth-first enumeration of the mine assignments of a component, safe is tried before mine for each variable, so
+/// the order is deterministic
+#[derive(Clone, Debug)]
+pub(crate) struct ComponentSolutions {
+    /// (target, local variable indices) for each equation
+    equations: Vec<(Ax, Vec<usize>)>,
+    /// indices into `equations` for each local variable
+    equations_of: Vec<Vec<usize>>,
+    /// mines and unassigned variables so far for each equation
+    sums: Vec<(Ax, usize)>,
+    values: Vec<Option<bool>>,
+    pos: usize,
+    finished: bool,
+}
+
+impl ComponentSolutions {
+    pub(crate) fn new(problem: &ConstraintProblem, component: &ConstraintComponent) -> Self {
+        let local_id = |variable_id: &usize| component.variable_ids.binary_search(variable_id).ok();
+        let mut equations_of = vec![Vec::new(); component.variable_ids.len()];
+        let equations: Vec<(Ax, Vec<usize>)> = component
+            .equation_ids
+            .iter()
+            .enumerate()
+            .map(|(index, &equation_id)| {
+                let equation = &problem.equations[equation_id];
+                let local_ids: Vec<usize> =
+                    equation.variable_ids.iter().filter_map(local_id).collect();
+                for &local in &local_ids {
+                    equations_of[local].push(index);
+                }
+                (equation.mines, local_ids)
+            })
+            .collect();
+        let sums = equations.iter().map(|(_, ids)| (0, ids.len())).collect();
+        Self {
+            equations,
+            equations_of,
+            sums,
+            values: vec![None; component.variable_ids.len()],
+            pos: 0,
+            finished: component.variable_ids.is_empty(),
+        }
+    }
+
+    fn assign(&mut self, local: usize, value: bool) {
+        self.values[local] = Some(value);
+        for &index in &self.equations_of[local] {
+            let (mines, unassigned) = &mut self.sums[index];
+            *mines += Ax::from(value);
+            *unassigned -= 1;
+        }
+    }
+
+    fn unassign(&mut self, local: usize) {
+        let Some(value) = self.values[local].take() else {
+            return;
+        };
+        for &index in &self.equations_of[local] {
+            let (mines, unassigned) = &mut self.sums[index];
+            *mines -= Ax::from(value);
+            *unassigned += 1;
+        }
+    }
+
+    /// Whether the equations touching this variable can still be satisfied
+    fn is_consistent(&self, local: usize) -> bool {
+        self.equations_of[local].iter().all(|&index| {
+            let target = self.equations[index].0;
+            let (mines, unassigned) = self.sums[index];
+            mines <= target && usize::from(mines) + unassigned >= usize::from(target)
+        })
+    }
+}
+
+impl Iterator for ComponentSolutions {
+    type Item = Vec<bool>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let len = self.values.len();
+        if self.pos == len {
+            // resume after the previously returned solution
+            self.pos -= 1;
+        }
+        loop {
+            if self.pos == len {
+                return Some(self.values.iter().map(|value| value.unwrap()).collect());
+            }
+            let pos = self.pos;
+            match self.values[pos] {
+                None => self.assign(pos, false),
+                Some(false) => {
+                    self.unassign(pos);
+                    self.assign(pos, true);
+                }
+                Some(true) => {
+                    self.unassign(pos);
+                    if pos == 0 {
+                        self.finished = true;
+                        return None;
+                    }
+                    self.pos -= 1;
+                    continue;
+                }
+            }
+            if self.is_consistent(pos) {
+                self.pos += 1;
+            }
+        }
+    }
+}
+
+/// Every mine assignment of the component's variables (in the order of `component.variable_ids`, `true` for a mine)
+/// that satisfies all of its equations, ignoring the global mine count. Assignments are found one at a time by a
+/// depth-first search trying safe before mine for each variable, so the order is deterministic and only the current
+/// assignment is kept in memory.
+pub fn iter_component_solutions(
+    problem: &ConstraintProblem,
+    component: &ConstraintComponent,
+) -> impl Iterator<Item = Vec<bool>> {
+    ComponentSolutions::new(problem, component)
+}
+
+/// Mine probability of each variable of the component (in the order of `component.variable_ids`), weighting every
+/// valid assignment equally and ignoring the global mine count.
+///
+/// Returns `None` when the component is larger than [`ENUMERATION_LIMIT`] or has no valid assignment.
+pub fn component_probabilities(
+    problem: &ConstraintProblem,
+    component: &ConstraintComponent,
+) -> Option<Vec<f64>> {
+    if component.variable_ids.len() > ENUMERATION_LIMIT {
+        return None;
+    }
+    let mut mine_counts = vec![0u32; component.variable_ids.len()];
+    let mut total = 0u32;
+    for solution in ComponentSolutions::new(problem, component) {
+        total += 1;
+        for (count, mine) in mine_counts.iter_mut().zip(solution) {
+            *count += u32::from(mine);
+        }
+    }
+    if total == 0 {
+        return None;
+    }
+    Some(
+        mine_counts
+            .into_iter()
+            .map(|count| f64::from(count) / f64::from(total))
+            .collect(),
+    )
+}
+
+/// Fewest and most mines the component's variables can hold together, ignoring the global mine count.
+///
+/// Exact when the component is within [`ENUMERATION_LIMIT`] and has a valid assignment, otherwise loose bounds from
+/// the equations: disjoint equations must all be satisfied and every variable is covered by some equation.
+pub fn component_mine_bounds(
+    problem: &ConstraintProblem,
+    component: &ConstraintComponent,
+) -> (Ax, Ax) {
+    if component.variable_ids.len() <= ENUMERATION_LIMIT {
+        let bounds = ComponentSolutions::new(problem, component)
+            .map(|solution| solution.into_iter().map(Ax::from).sum::<Ax>())
+            .fold(None, |bounds: Option<(Ax, Ax)>, mines| {
+                Some(bounds.map_or((mines, mines), |(min, max)| {
+                    (min.min(mines), max.max(mines))
+                }))
+            });
+        if let Some(bounds) = bounds {
+            return bounds;
+        }
+    }
+
+    let mut covered = Vec::new();
+    let mut min: Ax = 0;
+    let mut max: Ax = 0;
+    for &equation_id in &component.equation_ids {
+        let equation = &problem.equations[equation_id];
+        max = max.saturating_add(equation.mines);
+        if equation.variable_ids.iter().all(|id| !covered.contains(id)) {
+            covered.extend_from_slice(&equation.variable_ids);
+            min = min.saturating_add(equation.mines);
+        }
+    }
+    let len = Ax::try_from(component.variable_ids.len()).unwrap_or(Ax::MAX);
+    (min.min(len), max.min(len))
+}
+
+/// Mines no clue accounts for and the number of unknown tiles outside every component they can be in, e.g. "2 mines
+/// somewhere in these 10 interior tiles".
+///
+/// The mines are the unflagged mines left minus the fewest each component must hold, see [`component_mine_bounds`], so
+/// it's an upper bound when the components could hold more. It is 0 when the mine count is contradicted.
+pub fn loose_mine_estimate(obs: &Observation) -> (Ax, usize) {
+    let ConstraintBuildOutput { problem, .. } = build_constraints(obs);
+    let mines_left = problem
+        .equations
+        .iter()
+        .find(|equation| equation.kind == EquationKind::Global)
+        .map_or(0, |equation| equation.mines);
+    let components = problem.components();
+    let forced = components
+        .iter()
+        .map(|component| component_mine_bounds(&problem, component).0)
+        .fold(0, Ax::saturating_add);
+    let constrained: usize = components
+        .iter()
+        .map(|component| component.variable_ids.len())
+        .sum();
+    (
+        mines_left.saturating_sub(forced),
+        problem.variables.len() - constrained,
+    )
+}
+
+/// Tiles proven to be safe or to be mines, kept sorted in row-major order without duplicates
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Deductions {
+    pub safe: Vec<Ix2>,
+    pub mines: Vec<Ix2>,
+}
+
+fn sort_row_major(coords: &mut Vec<Ix2>) {
+    coords.sort_unstable_by_key(|&(x, y)| (y, x));
+    coords.dedup();
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+
+    /// Union with other deductions
+    pub fn merge(&mut self, other: Deductions) {
+        self.safe.extend(other.safe);
+        self.mines.extend(other.mines);
+        sort_row_major(&mut self.safe);
+        sort_row_major(&mut self.mines);
+    }
+
+    /// Remove what is already known from `known`
+    fn without(mut self, known: &Deductions) -> Self {
+        self.safe.retain(|coords| !known.safe.contains(coords));
+        self.mines.retain(|coords| !known.mines.contains(coords));
+        self
+    }
+
+    fn from_variables(problem: &ConstraintProblem, safe: Vec<usize>, mines: Vec<usize>) -> Self {
+        let mut deductions = Self {
+            safe: safe.into_iter().map(|id| problem.variables[id]).collect(),
+            mines: mines.into_iter().map(|id| problem.variables[id]).collect(),
+        };
+        sort_row_major(&mut deductions.safe);
+        sort_row_major(&mut deductions.mines);
+        deductions
+    }
+}
+
+impl Observation {
+    /// Flag the deduced mines, so other solvers can build on them
+    pub fn assume(&mut self, deductions: &Deductions) {
+        for &coords in &deductions.mines {
+            if self.is_unknown(coords) {
+                self.grid[coords.convert()] = AnyTile::Flag;
+            }
+        }
+    }
+}
+
+/// Estimated mine probability of every unknown tile, `None` for the rest. Tiles of components within
+/// [`ENUMERATION_LIMIT`] get their [`component_probabilities`], tiles of larger components are left as `None` since
+/// no exact value is known for them. Tiles outside every component get the density of the mines the components don't
+/// account for, that density is unknown when the mine count is contradicted, those tiles are left as `None` then.
+pub fn estimate_probabilities(obs: &Observation) -> Array2<Option<f64>> {
+    let ConstraintBuildOutput { problem, .. } = build_constraints(obs);
+    probability_map(obs, &problem)
+}
+
+/// [`estimate_probabilities`] for the problem built from `obs`
+fn probability_map(obs: &Observation, problem: &ConstraintProblem) -> Array2<Option<f64>> {
+    let mut probabilities: Array2<Option<f64>> = Array2::default(obs.grid.dim());
+    let variable_probabilities = variable_probabilities(problem);
+    for (&coords, probability) in problem.variables.iter().zip(variable_probabilities) {
+        probabilities[coords.convert()] = probability;
+    }
+    probabilities
+}
+
+/// [`estimate_probabilities`] of each variable in the order of `problem.variables`
+fn variable_probabilities(problem: &ConstraintProblem) -> Vec<Option<f64>> {
+    let mut probabilities = vec![None; problem.variables.len()];
+    let mut in_component = vec![false; problem.variables.len()];
+    let mut expected_mines = 0.0;
+    let mut estimated = 0usize;
+    for component in problem.components() {
+        for &variable_id in &component.variable_ids {
+            in_component[variable_id] = true;
+        }
+        let Some(component_probabilities) = component_probabilities(problem, &component) else {
+            continue;
+        };
+        for (&variable_id, probability) in
+            component.variable_ids.iter().zip(component_probabilities)
+        {
+            probabilities[variable_id] = Some(probability);
+            expected_mines += probability;
+            estimated += 1;
+        }
+    }
+    let mines_left = problem
+        .equations
+        .iter()
+        .find(|equation| equation.kind == EquationKind::Global)
+        .map(|equation| f64::from(equation.mines));
+    let rest = problem.variables.len() - estimated;
+    if let Some(mines_left) = mines_left.filter(|_| rest > 0) {
+        // the mines of larger components aren't known either, they share the density without getting it
+        let density = ((mines_left - expected_mines) / rest as f64).clamp(0.0, 1.0);
+        for (probability, _) in probabilities
+            .iter_mut()
+            .zip(in_component)
+            .filter(|&(_, in_component)| !in_component)
+        {
+            *probability = Some(density);
+        }
+    }
+    probabilities
+}
+
+/// Expected Shannon information in bits gained by opening a variable, the entropy of what opening it shows: a mine
+/// or one of the counts it can have.
+///
+/// The chance of a mine is its [`estimate_probabilities`]. When the variable is safe its count is the number of mines
+/// among its neighboring variables, plus the flags around it which are the same in every case and add nothing. If the
+/// variable is in a component within [`ENUMERATION_LIMIT`], the neighbors in that component are counted over every
+/// assignment with the variable safe, weighted equally like [`component_probabilities`]. Every other neighbor is taken
+/// as an independent mine with its own estimated probability.
+///
+/// Returns 0 when the probability of the variable is unknown.
+pub fn expected_information(problem: &ConstraintProblem, variable_id: usize) -> f64 {
+    let probabilities = variable_probabilities(problem);
+    let Some(mine) = probabilities[variable_id] else {
+        return 0.0;
+    };
+    let coords = problem.variables[variable_id];
+    let is_neighbor = |other: Ix2| {
+        other != coords && other.0.abs_diff(coords.0) <= 1 && other.1.abs_diff(coords.1) <= 1
+    };
+    let neighbor_ids: Vec<usize> = (0..problem.variables.len())
+        .filter(|&id| is_neighbor(problem.variables[id]))
+        .collect();
+
+    // distribution of the mines among the neighbors when the variable is safe
+    let mut counts = vec![1.0];
+    let component = problem
+        .components()
+        .into_iter()
+        .find(|component| component.variable_ids.binary_search(&variable_id).is_ok())
+        .filter(|component| component.variable_ids.len() <= ENUMERATION_LIMIT);
+    let mut independent_ids = neighbor_ids.clone();
+    if let Some(component) = component {
+        let local_id = |id: &usize| component.variable_ids.binary_search(id).ok();
+        let local = local_id(&variable_id).unwrap();
+        let local_neighbors: Vec<usize> = neighbor_ids.iter().filter_map(local_id).collect();
+        independent_ids.retain(|id| local_id(id).is_none());
+        let mut histogram = vec![0u32; local_neighbors.len() + 1];
+        for solution in ComponentSolutions::new(problem, &component) {
+            if !solution[local] {
+                let mines = local_neighbors.iter().filter(|&&id| solution[id]).count();
+                histogram[mines] += 1;
+            }
+        }
+        let total: u32 = histogram.iter().sum();
+        if total > 0 {
+            counts = histogram
+                .into_iter()
+                .map(|count| f64::from(count) / f64::from(total))
+                .collect();
+        }
+    }
+    for id in independent_ids {
+        let p = probabilities[id].unwrap_or(0.0);
+        let mut next = vec![0.0; counts.len() + 1];
+        for (mines, &weight) in counts.iter().enumerate() {
+            next[mines] += weight * (1.0 - p);
+            next[mines + 1] += weight * p;
+        }
+        counts = next;
+    }
+
+    let entropy = |p: f64| if p > 0.0 { -p * libm::log2(p) } else { 0.0 };
+    entropy(mine)
+        + counts
+            .into_iter()
+            .map(|weight| entropy(weight * (1.0 - mine)))
+            .sum::<f64>()
+}
+
+impl Observation {
+    /// Unknown tiles sharing the lowest [`estimate_probabilities`], in row-major order
+    pub fn safest_tiles(&self) -> Vec<Ix2> {
+        // equal probabilities can come out of different sums
+        const EPSILON: f64 = 1e-9;
+        let probabilities = estimate_probabilities(self);
+        let Some(lowest) = probabilities.iter().flatten().copied().reduce(f64::min) else {
+            return Vec::new();
+        };
+        iter_coords(self.size())
+            .filter(|&coords| probabilities[coords.convert()].is_some_and(|p| p - lowest < EPSILON))
+            .collect()
+    }
+
+    /// One of the [`Observation::safest_tiles`] picked by `rng`, the pick only depends on the RNG's output so the same
+    /// seed picks the same tile
+    pub fn best_guess(&self, rng: &mut impl rand::RngCore) -> Option<Ix2> {
+        let tiles = self.safest_tiles();
+        if tiles.is_empty() {
+            return None;
+        }
+        let index = crate::generator::below(rng, tiles.len() as u64);
+        Some(tiles[index as usize])
+    }
+
+    /// The first of the [`Observation::safest_tiles`] in row-major order
+    pub fn best_guess_deterministic(&self) -> Option<Ix2> {
+        self.safest_tiles().first().copied()
+    }
+}
+
+/// Deduction strategy, implement it to plug custom heuristics into [`Game::auto_open_trivial`]
+pub trait Solver {
+    fn deduce(&self, obs: &Observation) -> Deductions;
+
+    /// Combine with another solver, the result runs both until neither finds anything new
+    fn then<S: Solver>(self, other: S) -> Then<Self, S>
+    where
+        Self: Sized,
+    {
+        Then(self, other)
+    }
+}
+
+impl<S: Solver + ?Sized> Solver for &S {
+    fn deduce(&self, obs: &Observation) -> Deductions {
+        (**self).deduce(obs)
+    }
+}
+
+/// See [`Solver::then`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Then<A, B>(pub A, pub B);
+
+impl<A: Solver, B: Solver> Solver for Then<A, B> {
+    fn deduce(&self, obs: &Observation) -> Deductions {
+        let mut obs = obs.clone();
+        let mut deductions = Deductions::default();
+        loop {
+            let mut found = self.0.deduce(&obs);
+            obs.assume(&found);
+            found.merge(self.1.deduce(&obs));
+            let found = found.without(&deductions);
+            if found.is_empty() {
+                return deductions;
+            }
+            obs.assume(&found);
+            deductions.merge(found);
+        }
+    }
+}
+
+/// Single equation rules: no mines left means all safe, as many mines as variables means all mines
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct TrivialSolver;
+
+impl Solver for TrivialSolver {
+    fn deduce(&self, obs: &Observation) -> Deductions {
+        let ConstraintBuildOutput { problem, .. } = build_constraints(obs);
+        let mut safe = Vec::new();
+        let mut mines = Vec::new();
+        for equation in &problem.equations {
+            if equation.mines == 0 {
+                safe.extend_from_slice(&equation.variable_ids);
+            } else if usize::from(equation.mines) == equation.variable_ids.len() {
+                mines.extend_from_slice(&equation.variable_ids);
+            }
+        }
+        Deductions::from_variables(&problem, safe, mines)
+    }
+}
+
+/// Pairwise rule: when the variables of one equation are a subset of another's, the remaining variables of the larger
+/// one hold the difference of their mines
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct SubsetSolver;
+
+impl Solver for SubsetSolver {
+    fn deduce(&self, obs: &Observation) -> Deductions {
+        let ConstraintBuildOutput { problem, .. } = build_constraints(obs);
+        // build_constraints keeps the variables of each equation sorted, needed by the binary searches
+        debug_assert!(problem
+            .equations
+            .iter()
+            .all(|equation| equation.variable_ids.is_sorted()));
+        let mut safe = Vec::new();
+        let mut mines = Vec::new();
+        for (small_id, small) in problem.equations.iter().enumerate() {
+            for (large_id, large) in problem.equations.iter().enumerate() {
+                if small_id == large_id || small.variable_ids.len() >= large.variable_ids.len() {
+                    continue;
+                }
+                let Some(diff_mines) = large.mines.checked_sub(small.mines) else {
+                    continue;
+                };
+                let is_subset = small
+                    .variable_ids
+                    .iter()
+                    .all(|id| large.variable_ids.binary_search(id).is_ok());
+                if !is_subset {
+                    continue;
+                }
+                let diff_len = large.variable_ids.len() - small.variable_ids.len();
+                let diff = large
+                    .variable_ids
+                    .iter()
+                    .filter(|id| small.variable_ids.binary_search(id).is_err());
+                if diff_mines == 0 {
+                    safe.extend(diff);
+                } else if usize::from(diff_mines) == diff_len {
+                    mines.extend(diff);
+                }
+            }
+        }
+        Deductions::from_variables(&problem, safe, mines)
+    }
+}
+
+/// Whether the position is fully resolved: the built-in solvers prove every unknown tile to be either safe or a mine,
+/// so opening the safe ones wins without any guess. Flags are trusted.
+pub fn is_trivially_won(obs: &Observation) -> bool {
+    let deductions = TrivialSolver.then(SubsetSolver).deduce(obs);
+    iter_coords(obs.size())
+        .filter(|&coords| obs.is_unknown(coords))
+        .all(|coords| deductions.safe.contains(&coords) || deductions.mines.contains(&coords))
+}
+
+/// What the analysis knows about a tile, see [`classify_cells`]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CellConfidence {
+    /// Not an unknown tile: open, flagged or exploded
+    Revealed,
+    ProvenSafe,
+    ProvenMine,
+    /// Mine probability of a tile that isn't proven either way
+    Probable(f64),
+    /// Nothing can be estimated, the mine count is contradicted
+    Unknown,
+}
+
+/// Classify every tile for overlays in one pass: tiles the built-in solvers prove to be safe or mines, the rest with
+/// its [`estimate_probabilities`]. Flags are trusted.
+pub fn classify_cells(obs: &Observation) -> Array2<CellConfidence> {
+    AnalysisResult::new(obs).cells()
+}
+
+/// Everything [`classify_cells`] computes for a position, serializable so it can be cached by
+/// [`Observation::fingerprint`] instead of running the solvers again
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AnalysisResult {
+    pub fingerprint: u64,
+    pub constraints: ConstraintBuildOutput,
+    /// What the built-in solvers prove, flags are trusted
+    pub deductions: Deductions,
+    /// Same as [`estimate_probabilities`]
+    pub probabilities: Array2<Option<f64>>,
+}
+
+impl AnalysisResult {
+    pub fn new(obs: &Observation) -> Self {
+        let constraints = build_constraints(obs);
+        let probabilities = probability_map(obs, &constraints.problem);
+        Self {
+            fingerprint: obs.fingerprint(),
+            deductions: TrivialSolver.then(SubsetSolver).deduce(obs),
+            constraints,
+            probabilities,
+        }
+    }
+
+    /// Same as [`classify_cells`] for the analyzed observation
+    pub fn cells(&self) -> Array2<CellConfidence> {
+        let mut cells = Array2::from_elem(self.probabilities.dim(), CellConfidence::Revealed);
+        for &coords in &self.constraints.problem.variables {
+            cells[coords.convert()] = if self.deductions.safe.contains(&coords) {
+                CellConfidence::ProvenSafe
+            } else if self.deductions.mines.contains(&coords) {
+                CellConfidence::ProvenMine
+            } else if let Some(probability) = self.probabilities[coords.convert()] {
+                CellConfidence::Probable(probability)
+            } else {
+                CellConfidence::Unknown
+            };
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{field, now, obs};
+
+    fn probabilities(o: &Observation) -> Vec<f64> {
+        let out = build_constraints(o);
+        let components = out.problem.components();
+        assert_eq!(components.len(), 1);
+        component_probabilities(&out.problem, &components[0]).unwrap()
+    }
+
+    #[test]
+    fn enumerates_single_clue() {
+        let o = obs(&["###", "#1#", "###"], 1);
+        let out = build_constraints(&o);
+        assert!(out.contradictions.is_empty());
+        let components = out.problem.components();
+        assert_eq!(
+            ComponentSolutions::new(&out.problem, &components[0]).count(),
+            8
+        );
+        assert!(probabilities(&o).iter().all(|&p| (p - 0.125).abs() < 1e-9));
+    }
+
+    #[test]
+    fn probabilities_of_known_patterns() {
+        assert_eq!(probabilities(&obs(&["##", "11", "00"], 1)), vec![0.5, 0.5]);
+        assert_eq!(probabilities(&obs(&["#1", "11"], 1)), vec![1.0]);
+        assert_eq!(
+            probabilities(&obs(&["###", "121", "000"], 2)),
+            vec![1.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn reports_contradictions() {
+        let out = build_constraints(&obs(&["FF", "1#"], 1));
+        assert_eq!(
+            out.contradictions,
+            vec![
+                Contradiction::LocalClueImpossible((0, 1)),
+                Contradiction::GlobalMineCountImpossible
+            ]
+        );
+    }
+
+    /// Knows the top left corner is a mine, something the built-in solvers cannot prove on their own
+    struct CornerSolver;
+
+    impl Solver for CornerSolver {
+        fn deduce(&self, obs: &Observation) -> Deductions {
+            let mut deductions = Deductions::default();
+            if obs.is_unknown((0, 0)) {
+                deductions.mines.push((0, 0));
+            }
+            deductions
+        }
+    }
+
+    #[test]
+    fn subset_solver_resolves_one_two_one() {
+        let o = obs(&["###", "121", "000"], 2);
+        assert!(TrivialSolver.deduce(&o).is_empty());
+        let deductions = SubsetSolver.deduce(&o);
+        assert_eq!(deductions.mines, vec![(0, 0), (2, 0)]);
+        assert!(deductions.safe.is_empty());
+        let deductions = TrivialSolver.then(SubsetSolver).deduce(&o);
+        assert_eq!(deductions.mines, vec![(0, 0), (2, 0)]);
+        assert_eq!(deductions.safe, vec![(1, 0)]);
+    }
+
+    #[test]
+    fn custom_solver_unlocks_deductions() {
+        let o = obs(&["##", "1#"], 1);
+        assert!(TrivialSolver.then(SubsetSolver).deduce(&o).is_empty());
+        let deductions = CornerSolver.then(TrivialSolver).deduce(&o);
+        assert_eq!(deductions.mines, vec![(0, 0)]);
+        assert_eq!(deductions.safe, vec![(1, 0), (1, 1)]);
+    }
+
+    fn single_component_bounds(o: &Observation) -> (Ax, Ax) {
+        let problem = build_constraints(o).problem;
+        let components = problem.components();
+        assert_eq!(components.len(), 1);
+        component_mine_bounds(&problem, &components[0])
+    }
+
+    #[test]
+    fn mine_bounds_tight_and_ranged() {
+        assert_eq!(
+            single_component_bounds(&obs(&["###", "121", "000"], 2)),
+            (2, 2)
+        );
+        assert_eq!(
+            single_component_bounds(&obs(&["###0", "#100", "0000"], 5)),
+            (1, 1)
+        );
+        assert_eq!(single_component_bounds(&obs(&["####", "#11#"], 5)), (1, 2));
+    }
+
+    #[test]
+    fn trivially_won_positions() {
+        // one mine left to deduce, the rest is already open
+        assert!(is_trivially_won(&obs(&["#1", "11"], 1)));
+        assert!(is_trivially_won(&obs(&["###", "121", "000"], 2)));
+        // only the global count resolves it
+        assert!(is_trivially_won(&obs(&["F#", "##"], 1)));
+        // a 50/50
+        assert!(!is_trivially_won(&obs(&["##", "11"], 1)));
+    }
+
+    #[test]
+    fn validate_flags_finds_overflagged_clues() {
+        assert_eq!(
+            validate_flags(&obs(&["FF#", "1##", "###"], 3)),
+            vec![(0, 0), (1, 0)]
+        );
+        assert!(validate_flags(&obs(&["F##", "1##", "###"], 3)).is_empty());
+        // more flags than mines
+        assert_eq!(
+            validate_flags(&obs(&["FF#", "###", "###"], 1)),
+            vec![(0, 0), (1, 0)]
+        );
+
+        let out = build_constraints_with(&obs(&["FF#", "1##", "###"], 3), FlagSemantics::Ignore);
+        assert!(out.contradictions.is_empty());
+        assert_eq!(out.problem.variables.len(), 8);
+    }
+
+    #[test]
+    fn best_guess_ties_follow_the_seed() {
+        let o = obs(&["####", "####", "####"], 3);
+        assert!((estimate_probabilities(&o)[(0, 0)].unwrap() - 0.25).abs() < 1e-9);
+        assert_eq!(o.safest_tiles().len(), 12);
+        assert_eq!(o.best_guess_deterministic(), Some((0, 0)));
+        let pick = |seed| o.best_guess(&mut SplitMix64::new(seed)).unwrap();
+        assert_eq!(pick(5), pick(5));
+        assert!((0..20).any(|seed| pick(seed) != pick(0)));
+    }
+
+    #[test]
+    fn estimate_spreads_leftover_density() {
+        let o = obs(&["1##", "###", "###"], 1);
+        let probabilities = estimate_probabilities(&o);
+        assert_eq!(probabilities[(0, 0)], None);
+        assert!((probabilities[(1, 0)].unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert!(probabilities[(2, 2)].unwrap().abs() < 1e-9);
+        assert_eq!(o.best_guess_deterministic(), Some((2, 0)));
+    }
+
+    #[test]
+    fn large_components_have_no_estimate() {
+        let row = |tile: &str| tile.repeat(ENUMERATION_LIMIT + 2);
+        let rows = [row("#"), row("1"), row("0"), row("#"), row("#")];
+        let rows: Vec<&str> = rows.iter().map(|row| row.as_str()).collect();
+        let probabilities = estimate_probabilities(&obs(&rows, 10));
+        assert_eq!(probabilities[(0, 0)], None);
+        assert_eq!(probabilities[(0, 3)], None);
+        let density = 10.0 / (3 * (ENUMERATION_LIMIT + 2)) as f64;
+        assert!((probabilities[(0, 4)].unwrap() - density).abs() < 1e-9);
+    }
+
+    #[test]
+    fn constrained_tile_is_more_informative() {
+        let o = obs(&["#####", "#1#1#", "#####", "#####", "#####"], 3);
+        let problem = build_constraints(&o).problem;
+        let id = |coords: Ix2| problem.variables.iter().position(|&v| v == coords).unwrap();
+        // between both clues versus far from any
+        let shared = expected_information(&problem, id((2, 1)));
+        let isolated = expected_information(&problem, id((4, 4)));
+        assert!(shared > isolated);
+        assert!(isolated > 0.0);
+    }
+
+    #[test]
+    fn classify_proven_and_probable() {
+        let cells = classify_cells(&obs(&["#1", "11"], 1));
+        assert_eq!(cells[(0, 0)], CellConfidence::ProvenMine);
+        assert_eq!(cells[(1, 0)], CellConfidence::Revealed);
+
+        let cells = classify_cells(&obs(&["##", "11", "00"], 1));
+        assert_eq!(cells[(0, 0)], CellConfidence::Probable(0.5));
+        assert_eq!(cells[(1, 0)], CellConfidence::Probable(0.5));
+
+        let cells = classify_cells(&obs(&["###", "111", "000"], 1));
+        assert_eq!(cells[(0, 0)], CellConfidence::ProvenSafe);
+        assert_eq!(cells[(2, 0)], CellConfidence::ProvenSafe);
+    }
+
+    #[test]
+    fn iterates_solutions_in_order() {
+        let o = obs(&["1#", "##"], 1);
+        let problem = build_constraints(&o).problem;
+        let components = problem.components();
+        assert_eq!(components.len(), 1);
+        let solutions: Vec<_> = iter_component_solutions(&problem, &components[0]).collect();
+        assert_eq!(
+            solutions,
+            [
+                vec![false, false, true],
+                vec![false, true, false],
+                vec![true, false, false]
+            ]
+        );
+    }
+
+    #[test]
+    fn loose_mines() {
+        // the clues force one mine in the third row, the other is somewhere in the last row
+        assert_eq!(
+            loose_mine_estimate(&obs(&["000", "111", "###", "###"], 2)),
+            (1, 3)
+        );
+        assert_eq!(
+            loose_mine_estimate(&obs(&["000", "111", "###", "###"], 1)),
+            (0, 3)
+        );
+        assert_eq!(loose_mine_estimate(&obs(&["1#", "##"], 1)), (0, 0));
+    }
+
+    #[test]
+    fn updated_observation_matches_a_fresh_one() {
+        let mut game = Game::new(field(&["..*..", "..*..", "..*..", ".....", "....*"]));
+        let mut obs = Observation::from_game(&game);
+        let opened =
+            |depths: Vec<(Ix2, u16)>| depths.into_iter().map(|(pos, _)| pos).collect::<Vec<_>>();
+
+        let (_, depths) = game.open_with_depths((0, 0), now()).unwrap();
+        obs.apply_changes(&game, &opened(depths)).unwrap();
+        assert_eq!(obs, Observation::from_game(&game));
+
+        game.flag((2, 0)).unwrap();
+        obs.apply_changes(&game, &[(2, 0)]).unwrap();
+        assert_eq!(obs, Observation::from_game(&game));
+
+        let (_, depths) = game.open_with_depths((3, 0), now()).unwrap();
+        obs.apply_changes(&game, &opened(depths)).unwrap();
+        assert_eq!(obs, Observation::from_game(&game));
+
+        game.flag((2, 1)).unwrap();
+        obs.apply_changes(&game, &[(2, 1)]).unwrap();
+        let fresh = Observation::from_game(&game);
+        assert_eq!(obs, fresh);
+
+        assert!(matches!(
+            obs.apply_changes(&game, &[(5, 0)]),
+            Err(GameError::InvalidCoords)
+        ));
+        assert_eq!(obs, fresh);
+    }
+
+    #[test]
+    fn fingerprint_tells_observations_apart() {
+        let obs1 = obs(&["1#", "##"], 1);
+        assert_eq!(obs1.fingerprint(), obs(&["1#", "##"], 1).fingerprint());
+        assert_ne!(obs1.fingerprint(), obs(&["1#", "##"], 2).fingerprint());
+        assert_ne!(obs1.fingerprint(), obs(&["1#", "#1"], 1).fingerprint());
+
+        let filled = |tile| {
+            Observation::new(Array2::from_elem((2, 1), tile), 1)
+                .unwrap()
+                .fingerprint()
+        };
+        assert_ne!(filled(AnyTile::Open(9)), filled(AnyTile::Open(12)));
+        assert_ne!(filled(AnyTile::Open(9)), filled(AnyTile::Closed));
+    }
+
+    #[test]
+    fn analysis_result_round_trips() {
+        let obs = obs(&["1##", "###", "##1"], 2);
+        let analysis = AnalysisResult::new(&obs);
+        assert_eq!(analysis.cells(), classify_cells(&obs));
+        assert_eq!(analysis.probabilities, estimate_probabilities(&obs));
+
+        let json = serde_json::to_string(&analysis).unwrap();
+        let loaded: AnalysisResult = serde_json::from_str(&json).unwrap();
+        let fresh = AnalysisResult::new(&obs);
+        assert_eq!(loaded.fingerprint, fresh.fingerprint);
+        assert_eq!(loaded.constraints, fresh.constraints);
+        assert_eq!(loaded.deductions, fresh.deductions);
+        // the text form of a float can be off by the last bit
+        for (loaded, fresh) in loaded.probabilities.iter().zip(&fresh.probabilities) {
+            assert_eq!(loaded.is_some(), fresh.is_some());
+            assert!((loaded.unwrap_or(0.0) - fresh.unwrap_or(0.0)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn constraint_variables_are_sorted() {
+        let mut game = Game::new(field(&["*..*", "....", ".*..", "...."]));
+        game.open((1, 1), now()).unwrap();
+        game.open((2, 1), now()).unwrap();
+        let ConstraintBuildOutput { problem, .. } =
+            build_constraints(&Observation::from_game(&game));
+        assert_eq!(problem.equations.len(), 3);
+        assert!(problem
+            .equations
+            .iter()
+            .all(|eq| eq.variable_ids.is_sorted()));
+        assert!(problem.variables.is_sorted_by_key(|&(x, y)| (y, x)));
+        assert_eq!(
+            problem.equations.last().map(|eq| eq.kind),
+            Some(EquationKind::Global)
+        );
+    }
+}
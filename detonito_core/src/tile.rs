@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 
 // Define your enum for tile state and make it JS-compatible
-#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub enum AnyTile {
+    #[default]
     Closed,
     Open(u8),
     Flag,
@@ -26,11 +27,29 @@ impl AnyTile {
             IncorrectFlag => true,
         }
     }
-}
 
-impl Default for AnyTile {
-    fn default() -> Self {
-        Self::Closed
+    /// Stable numeric code for renderers:
+    ///
+    /// | code | tile |
+    /// |------|------|
+    /// | 0..=8 | open, the code is the adjacent mine count |
+    /// | 9 | closed |
+    /// | 10 | flag |
+    /// | 11 | question |
+    /// | 12 | exploded mine |
+    /// | 13 | mine revealed after a loss |
+    /// | 14 | flag on a safe tile revealed after a loss |
+    pub fn code(self) -> u8 {
+        use AnyTile::*;
+        match self {
+            Open(count) => count.min(8),
+            Closed => 9,
+            Flag => 10,
+            Question => 11,
+            Exploded => 12,
+            Mine => 13,
+            IncorrectFlag => 14,
+        }
     }
 }
 
@@ -65,3 +84,27 @@ impl From<WinTile> for AnyTile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_match_table() {
+        use AnyTile::*;
+
+        for (tile, code) in [
+            (Open(0), 0),
+            (Open(3), 3),
+            (Open(8), 8),
+            (Closed, 9),
+            (Flag, 10),
+            (Question, 11),
+            (Exploded, 12),
+            (Mine, 13),
+            (IncorrectFlag, 14),
+        ] {
+            assert_eq!(tile.code(), code);
+        }
+    }
+}
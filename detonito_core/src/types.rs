@@ -27,6 +27,53 @@ pub const fn mult(a: Ix, b: Ix) -> Ax {
     a.saturating_mul(b)
 }
 
+/// All coordinates within `size` in row-major order (`y` then `x`)
+pub(crate) fn iter_coords((size_x, size_y): Ix2) -> impl Iterator<Item = Ix2> {
+    (0..size_y).flat_map(move |y| (0..size_x).map(move |x| (x, y)))
+}
+
+/// Tiles up to `radius` away from `center` in both axes (the Chebyshev distance) within `size`, row-major, the center
+/// excluded, a radius of 1 gives the adjacent tiles
+pub(crate) fn iter_region(
+    (x, y): Ix2,
+    radius: u8,
+    (size_x, size_y): Ix2,
+) -> impl Iterator<Item = Ix2> {
+    let span =
+        |pos: Ix, len: Ix| pos.saturating_sub(radius)..=pos.saturating_add(radius).min(len - 1);
+    let xs = span(x, size_x);
+    span(y, size_y)
+        .flat_map(move |ny| xs.clone().map(move |nx| (nx, ny)))
+        .filter(move |&pos| pos != (x, y))
+}
+
+/// Shape of an array as [`Ix2`], axes longer than [`Ix::MAX`] saturate instead of panicking, constructors reject those
+/// with [`GameError::InvalidBoardShape`](crate::GameError::InvalidBoardShape)
+pub(crate) fn shape_of<T>(array: &Array2<T>) -> Ix2 {
+    let (dim_x, dim_y) = array.dim();
+    let saturate = |dim: usize| Ix::try_from(dim).unwrap_or(Ix::MAX);
+    (saturate(dim_x), saturate(dim_y))
+}
+
+/// Neighbors of a tile within the array bounds, diagonals included, top row first. Tiles on an edge or in a corner
+/// of any shape, even a single row, column or tile, only get the neighbors that exist:
+///
+/// ```
+/// use detonito_core::*;
+/// use ndarray::Array2;
+///
+/// let neighbors = |size: Ix2, coords: Ix2| -> Vec<Ix2> {
+///     Array2::<bool>::default((usize::from(size.0), usize::from(size.1))).iter_adjacent(coords).collect()
+/// };
+/// assert_eq!(neighbors((1, 1), (0, 0)), []);
+/// assert_eq!(neighbors((3, 1), (0, 0)), [(1, 0)]);
+/// assert_eq!(neighbors((3, 1), (1, 0)), [(0, 0), (2, 0)]);
+/// assert_eq!(neighbors((1, 3), (0, 2)), [(0, 1)]);
+/// assert_eq!(neighbors((3, 3), (2, 2)), [(1, 1), (2, 1), (1, 2)]);
+/// assert_eq!(neighbors((3, 3), (1, 0)), [(0, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+/// assert_eq!(neighbors((3, 3), (1, 1)).len(), 8);
+/// assert_eq!(neighbors((255, 255), (254, 254)), [(253, 253), (254, 253), (253, 254)]);
+/// ```
 pub trait AdjacentIterator {
     // XXX: returning a impl Iterator seems to imply a &self borrow, using concrete type for now
     //fn iter_adjacent(&self, index: Ix2) -> impl Iterator<Item = Ix2>;
@@ -36,9 +83,7 @@ pub trait AdjacentIterator {
 impl<T> AdjacentIterator for Array2<T> {
     //fn iter_adjacent(&self, index: Ix2) -> impl Iterator<Item = Ix2> {
     fn iter_adjacent(&self, index: Ix2) -> IterAdjacent {
-        let dim = self.dim();
-        let size = (dim.0.try_into().unwrap(), dim.1.try_into().unwrap());
-        IterAdjacent::new(index, size)
+        IterAdjacent::new(index, shape_of(self))
     }
 }
 
@@ -85,6 +130,13 @@ fn apply_delta(coords: Ix2, delta: (isize, isize), bounds: Ix2) -> Option<Ix2> {
     Some((nx, ny))
 }
 
+/// Every displacement applied to `center`, `None` when out of bounds, plus how many are in bounds
+pub(crate) fn adjacent_array(center: Ix2, bounds: Ix2) -> ([Option<Ix2>; 8], u8) {
+    let adjacent = DISPLACEMENTS.map(|delta| apply_delta(center, delta, bounds));
+    let count = adjacent.iter().filter(|pos| pos.is_some()).count() as u8;
+    (adjacent, count)
+}
+
 #[derive(Debug)]
 pub struct IterAdjacent {
     center: Ix2,
@@ -118,3 +170,77 @@ impl Iterator for IterAdjacent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn iter_coords_is_row_major() {
+        assert_eq!(
+            iter_coords((2, 2)).collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (0, 1), (1, 1)]
+        );
+    }
+
+    /// Neighbors of `center` in row-major order, found by checking all eight offsets with signed arithmetic
+    fn expected_neighbors(center: Ix2, bounds: Ix2) -> Vec<Ix2> {
+        let mut neighbors = Vec::new();
+        for dy in -1i32..=1 {
+            for dx in -1i32..=1 {
+                let x = i32::from(center.0) + dx;
+                let y = i32::from(center.1) + dy;
+                if (dx, dy) != (0, 0)
+                    && x >= 0
+                    && y >= 0
+                    && x < i32::from(bounds.0)
+                    && y < i32::from(bounds.1)
+                {
+                    neighbors.push((x as Ix, y as Ix));
+                }
+            }
+        }
+        neighbors
+    }
+
+    #[test]
+    fn neighbors_at_every_edge() {
+        let mut shapes = Vec::new();
+        for size_x in 1..=6 {
+            for size_y in 1..=6 {
+                shapes.push((size_x, size_y));
+            }
+        }
+        shapes.extend([
+            (Ix::MAX, 1),
+            (1, Ix::MAX),
+            (Ix::MAX, Ix::MAX),
+            (Ix::MAX, 3),
+            (3, Ix::MAX),
+        ]);
+        for bounds in shapes {
+            let grid: Array2<bool> = Array2::default(bounds.convert());
+            for center in iter_coords(bounds) {
+                let expected = expected_neighbors(center, bounds);
+                let neighbors: Vec<Ix2> = grid.iter_adjacent(center).collect();
+                assert_eq!(neighbors, expected, "{:?} on {:?}", center, bounds);
+                let (array, count) = adjacent_array(center, bounds);
+                assert_eq!(usize::from(count), expected.len());
+                assert_eq!(array.iter().flatten().count(), expected.len());
+            }
+        }
+    }
+
+    #[test]
+    fn region_is_clipped_to_the_board() {
+        let region: Vec<Ix2> = iter_region((0, 0), 1, (3, 3)).collect();
+        assert_eq!(region, [(1, 0), (0, 1), (1, 1)]);
+        assert_eq!(iter_region((2, 2), 2, (5, 5)).count(), 24);
+        assert_eq!(
+            iter_region((Ix::MAX - 1, Ix::MAX - 1), 2, (Ix::MAX, Ix::MAX)).count(),
+            8
+        );
+    }
+}
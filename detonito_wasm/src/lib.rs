@@ -0,0 +1,234 @@
+//! Plain JavaScript bindings for `detonito-core`, for people who want to build their own UI.
+//!
+//! ```js
+//! import init, { new_game, Cell, State } from "./detonito_wasm.js";
+//!
+//! await init();
+//! const game = new_game(9, 9, 10, 42n);
+//! game.reveal(4, 4);
+//! if (game.cell_at(0, 0) === Cell.Closed) {
+//!     game.toggle_flag(0, 0);
+//! }
+//! game.chord(4, 4);
+//! console.log(game.state() === State.InProgress);
+//! ```
+
+use chrono::prelude::*;
+use detonito_core as game;
+use wasm_bindgen::prelude::*;
+
+fn utc_now() -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp_millis(js_sys::Date::now() as i64).unwrap()
+}
+
+/// Flat tile state, the discriminants are the codes of [`game::AnyTile::code`], so `Open0`..`Open8` share theirs with
+/// the adjacent mine count
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Cell {
+    Open0 = 0,
+    Open1 = 1,
+    Open2 = 2,
+    Open3 = 3,
+    Open4 = 4,
+    Open5 = 5,
+    Open6 = 6,
+    Open7 = 7,
+    Open8 = 8,
+    Closed = 9,
+    Flag = 10,
+    Question = 11,
+    Exploded = 12,
+    Mine = 13,
+    IncorrectFlag = 14,
+}
+
+impl From<game::AnyTile> for Cell {
+    fn from(tile: game::AnyTile) -> Self {
+        use game::AnyTile::*;
+        match tile {
+            Closed => Cell::Closed,
+            Open(0) => Cell::Open0,
+            Open(1) => Cell::Open1,
+            Open(2) => Cell::Open2,
+            Open(3) => Cell::Open3,
+            Open(4) => Cell::Open4,
+            Open(5) => Cell::Open5,
+            Open(6) => Cell::Open6,
+            Open(7) => Cell::Open7,
+            Open(_) => Cell::Open8,
+            Flag => Cell::Flag,
+            Question => Cell::Question,
+            Exploded => Cell::Exploded,
+            Mine => Cell::Mine,
+            IncorrectFlag => Cell::IncorrectFlag,
+        }
+    }
+}
+
+/// Mirror of [`game::GameState`]
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum State {
+    NotStarted,
+    InProgress,
+    Win,
+    Lose,
+    InstantWin,
+    InstantLoss,
+}
+
+impl From<game::GameState> for State {
+    fn from(state: game::GameState) -> Self {
+        use game::GameState::*;
+        match state {
+            NotStarted => State::NotStarted,
+            InProgress => State::InProgress,
+            Win => State::Win,
+            Lose => State::Lose,
+            InstantWin => State::InstantWin,
+            InstantLoss => State::InstantLoss,
+        }
+    }
+}
+
+/// Mirror of [`game::OpenOutcome`]
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Outcome {
+    NoChange,
+    Safe,
+    Explode,
+    Win,
+}
+
+impl From<game::OpenOutcome> for Outcome {
+    fn from(outcome: game::OpenOutcome) -> Self {
+        use game::OpenOutcome::*;
+        match outcome {
+            NoChange => Outcome::NoChange,
+            Safe => Outcome::Safe,
+            Explode => Outcome::Explode,
+            Win => Outcome::Win,
+        }
+    }
+}
+
+/// A game whose minefield is generated on the first reveal, so the first click is always a zero when possible
+#[wasm_bindgen]
+pub struct Game {
+    config: game::GameConfig,
+    seed: u64,
+    game: Option<game::Game>,
+}
+
+/// Start a new game, the mine count is clamped to fit the board
+#[wasm_bindgen]
+pub fn new_game(width: game::Ix, height: game::Ix, mines: game::Ax, seed: u64) -> Game {
+    Game {
+        config: game::GameConfig::new((width, height), mines),
+        seed,
+        game: None,
+    }
+}
+
+impl Game {
+    fn validate_coords(&self, coords: game::Ix2) -> game::Result<game::Ix2> {
+        let size = self.config.size;
+        if coords.0 < size.0 && coords.1 < size.1 {
+            Ok(coords)
+        } else {
+            Err(game::GameError::InvalidCoords)
+        }
+    }
+
+    fn get_or_create_game(&mut self, coords: game::Ix2) -> &mut game::Game {
+        use game::{MinefieldGenerator, RandomMinefieldGenerator, StartTile};
+        let Self { config, seed, game } = self;
+        game.get_or_insert_with(|| {
+            let minefield = RandomMinefieldGenerator::new(*seed, coords, StartTile::AlwaysZero)
+                .generate(*config);
+            game::Game::new(minefield)
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    pub fn width(&self) -> game::Ix {
+        self.config.size.0
+    }
+
+    pub fn height(&self) -> game::Ix {
+        self.config.size.1
+    }
+
+    pub fn mines(&self) -> game::Ax {
+        self.config.mines
+    }
+
+    /// Open a closed tile, the first call generates the minefield
+    pub fn reveal(&mut self, x: game::Ix, y: game::Ix) -> Result<Outcome, JsError> {
+        let coords = self.validate_coords((x, y))?;
+        let outcome = self.get_or_create_game(coords).open(coords, utc_now())?;
+        Ok(outcome.into())
+    }
+
+    /// Flag a closed tile or remove the flag from a flagged one, nothing happens before the first reveal
+    pub fn toggle_flag(&mut self, x: game::Ix, y: game::Ix) -> Result<bool, JsError> {
+        let coords = self.validate_coords((x, y))?;
+        let Some(game) = self.game.as_mut() else {
+            return Ok(false);
+        };
+        Ok(game.flag(coords)?.has_update())
+    }
+
+    /// Open all neighbors of an open tile when its count matches the adjacent flags
+    pub fn chord(&mut self, x: game::Ix, y: game::Ix) -> Result<Outcome, JsError> {
+        let coords = self.validate_coords((x, y))?;
+        let Some(game) = self.game.as_mut() else {
+            return Ok(Outcome::NoChange);
+        };
+        if !game.is_chordable(coords) {
+            return Ok(Outcome::NoChange);
+        }
+        Ok(game.chord_open(coords, utc_now())?.into())
+    }
+
+    pub fn cell_at(&self, x: game::Ix, y: game::Ix) -> Result<Cell, JsError> {
+        let coords = self.validate_coords((x, y))?;
+        Ok(self
+            .game
+            .as_ref()
+            .map_or(Cell::Closed, |game| game.tile_at(coords).into()))
+    }
+
+    /// [`Cell`] code of every tile in row-major order, all closed before the first reveal
+    pub fn render_codes(&self) -> Vec<u8> {
+        let Some(game) = self.game.as_ref() else {
+            return vec![Cell::Closed as u8; self.config.total_tiles().into()];
+        };
+        game.iter_cells().map(|(_, tile)| tile.code()).collect()
+    }
+
+    pub fn state(&self) -> State {
+        self.game
+            .as_ref()
+            .map_or(State::NotStarted, |game| game.cur_state().into())
+    }
+
+    /// Stable name of the state, e.g. `"in_progress"`
+    pub fn state_name(&self) -> String {
+        self.game
+            .as_ref()
+            .map_or(game::GameState::NotStarted, |game| game.cur_state())
+            .as_str()
+            .to_string()
+    }
+
+    pub fn mines_left(&self) -> i32 {
+        self.game
+            .as_ref()
+            .map_or(self.config.mines as i32, |game| game.mines_left() as i32)
+    }
+}
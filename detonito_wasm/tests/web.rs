@@ -0,0 +1,43 @@
+//! Test suite for the Web and headless browsers.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use detonito_wasm::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn first_reveal_opens_zero() {
+    let mut game = new_game(9, 9, 10, 42);
+    assert_eq!(game.state(), State::NotStarted);
+    assert_eq!(game.cell_at(4, 4).unwrap(), Cell::Closed);
+    assert_ne!(game.reveal(4, 4).unwrap(), Outcome::Explode);
+    assert_eq!(game.cell_at(4, 4).unwrap(), Cell::Open0);
+    assert!(matches!(
+        game.state(),
+        State::InProgress | State::InstantWin
+    ));
+}
+
+#[wasm_bindgen_test]
+fn flag_toggles() {
+    let mut game = new_game(9, 9, 10, 42);
+    game.reveal(4, 4).unwrap();
+    let closed = (0..9)
+        .flat_map(|x| (0..9).map(move |y| (x, y)))
+        .find(|&(x, y)| game.cell_at(x, y).unwrap() == Cell::Closed)
+        .unwrap();
+    assert!(game.toggle_flag(closed.0, closed.1).unwrap());
+    assert_eq!(game.cell_at(closed.0, closed.1).unwrap(), Cell::Flag);
+    assert_eq!(game.mines_left(), 9);
+    assert!(game.toggle_flag(closed.0, closed.1).unwrap());
+    assert_eq!(game.cell_at(closed.0, closed.1).unwrap(), Cell::Closed);
+}
+
+#[wasm_bindgen_test]
+fn invalid_coords_error() {
+    let mut game = new_game(9, 9, 10, 42);
+    assert!(game.reveal(9, 0).is_err());
+}
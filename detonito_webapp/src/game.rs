@@ -3,16 +3,94 @@ use chrono::prelude::*;
 use crate::utils::*;
 use bitflags::bitflags;
 use detonito_core as game;
+use gloo::events::EventListener;
 use gloo::timers::callback::Interval;
+use ndarray::Array2;
 use serde::{Deserialize, Serialize};
+use std::rc::Rc;
 use yew::prelude::*;
 
 fn utc_now() -> DateTime<Utc> {
     DateTime::<Utc>::from_timestamp_millis(js_sys::Date::now() as i64).unwrap()
 }
 
-impl StorageKey for game::Game {
-    const KEY: &'static str = "detonito:game";
+impl SlotStorageKey for game::Game {
+    const PREFIX: &'static str = "detonito:game";
+}
+
+/// Each game config gets its own save slot, so switching difficulty doesn't lose an in-progress game. Adaptive
+/// difficulty starts every game fresh on a new config, its games are kept apart so they never clear a saved game.
+fn game_slot(settings: &settings::Settings) -> String {
+    let config = &settings.game_config;
+    let slot = format!("{}x{}:{}", config.size.0, config.size.1, config.mines);
+    if settings.enable_adaptive_difficulty {
+        format!("adaptive:{}", slot)
+    } else {
+        slot
+    }
+}
+
+/// Results of finished games for adaptive difficulty, `true` for a win, oldest first
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct RecentResults(Vec<bool>);
+
+impl RecentResults {
+    const MAX_LEN: usize = 10;
+
+    fn push(&mut self, won: bool) {
+        self.0.push(won);
+        let excess = self.0.len().saturating_sub(Self::MAX_LEN);
+        self.0.drain(..excess);
+    }
+}
+
+impl StorageKey for RecentResults {
+    const KEY: &'static str = "detonito:results";
+}
+
+/// When each tile was opened, in milliseconds since the game started, saved next to the game in the same slot
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct RevealTimes(Array2<Option<u32>>);
+
+impl RevealTimes {
+    fn new(game: &game::Game) -> Self {
+        use game::NdConvert;
+        Self(Array2::default(game.size().convert()))
+    }
+
+    /// Record the open tiles without a time yet, returns whether any was recorded
+    fn record(&mut self, game: &game::Game, now: DateTime<Utc>) -> bool {
+        let millis = game.elapsed_millis(now);
+        let mut recorded = false;
+        for ((x, y), time) in self.0.indexed_iter_mut() {
+            if time.is_none() && game.is_open_at((x as game::Ix, y as game::Ix)) {
+                *time = Some(millis);
+                recorded = true;
+            }
+        }
+        recorded
+    }
+
+    /// How late a tile was opened, from 0 for the first tiles to 1 for the last ones
+    fn heat(&self, coords: game::Ix2) -> Option<f64> {
+        use game::NdConvert;
+        let last = self.0.iter().flatten().max().copied()?;
+        let time = self.0[coords.convert()]?;
+        Some(if last == 0 {
+            0.0
+        } else {
+            f64::from(time) / f64::from(last)
+        })
+    }
+}
+
+impl SlotStorageKey for RevealTimes {
+    const PREFIX: &'static str = "detonito:reveal-times";
+}
+
+/// Only the analysis of the last position is kept, enough to skip the solvers when reloading the page
+impl StorageKey for game::AnalysisResult {
+    const KEY: &'static str = "detonito:analysis";
 }
 
 pub trait HasUpdate {
@@ -31,6 +109,12 @@ impl<E> HasUpdate for Result<game::OpenOutcome, E> {
     }
 }
 
+impl<E> HasUpdate for Result<game::ActionOutcome, E> {
+    fn has_update(self) -> bool {
+        self.is_ok_and(|r| r.has_update())
+    }
+}
+
 bitflags! {
     #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
     struct MouseButtons: u16 {
@@ -42,6 +126,15 @@ bitflags! {
     }
 }
 
+/// Whether `buttons` start the two-button chord, `chord_press` then stays set until every button is released
+fn start_chord_press(chord_press: &mut bool, buttons: MouseButtons) -> bool {
+    if *chord_press || !buttons.contains(MouseButtons::LEFT | MouseButtons::RIGHT) {
+        return false;
+    }
+    *chord_press = true;
+    true
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) struct TileState {
     pos: (game::Ix, game::Ix),
@@ -50,6 +143,8 @@ pub(crate) struct TileState {
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum TileMsg {
+    /// A button went down, otherwise the same as `Update`
+    Down(TileState),
     Update(TileState),
     Leave,
 }
@@ -58,9 +153,71 @@ pub(crate) enum TileMsg {
 pub(crate) enum Msg {
     TileEvent(TileMsg),
     UpdateTime,
+    /// Every button was released somewhere or the window lost focus, forget any press in progress
+    CancelPress,
     NewGame,
+    ClearFlags,
+    ClearQuestions,
+    /// Flag every tile the solver proves to be a mine
+    FlagProvenMines,
     ToggleSettings,
     UpdateSettings(settings::Settings),
+    /// A change from the settings dialog or from the nav
+    DispatchSettings(settings::SettingsAction),
+}
+
+/// What the new game button shows, the game's state unless a tile is being pressed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum ViewGameState {
+    MidOpen,
+    Game(game::GameState),
+}
+
+impl ViewGameState {
+    /// Class main.scss styles this state with
+    pub fn css_class(&self) -> &'static str {
+        use game::GameState::*;
+        match self {
+            ViewGameState::MidOpen => "mid-open",
+            ViewGameState::Game(NotStarted) => "not-started",
+            ViewGameState::Game(InProgress) => "in-progress",
+            ViewGameState::Game(Win) => "win",
+            ViewGameState::Game(Lose) => "lose",
+            ViewGameState::Game(InstantWin) => "instant-win",
+            ViewGameState::Game(InstantLoss) => "instant-loss",
+        }
+    }
+}
+
+/// How a tile is drawn, besides the `cell` class every tile has
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct ViewCellState(pub game::AnyTile);
+
+impl ViewCellState {
+    /// Classes main.scss styles this tile with
+    pub fn css_classes(&self) -> &'static [&'static str] {
+        use game::AnyTile::*;
+        const OPEN: [[&str; 2]; 9] = [
+            ["open", "num-0"],
+            ["open", "num-1"],
+            ["open", "num-2"],
+            ["open", "num-3"],
+            ["open", "num-4"],
+            ["open", "num-5"],
+            ["open", "num-6"],
+            ["open", "num-7"],
+            ["open", "num-8"],
+        ];
+        match self.0 {
+            Closed => &[],
+            Open(count) => &OPEN[usize::from(count).min(8)],
+            Flag => &["flag"],
+            Question => &["question"],
+            Exploded => &["open", "mine", "oops"],
+            Mine => &["open", "mine"],
+            IncorrectFlag => &["flag", "wrong"],
+        }
+    }
 }
 
 #[derive(Properties, Clone, PartialEq)]
@@ -72,39 +229,45 @@ struct TileProps {
     pressed: bool,
     #[prop_or_default]
     locked: bool,
+    #[prop_or_default]
+    probability: Option<f64>,
+    /// How late the tile was opened, see [`RevealTimes::heat`]
+    #[prop_or_default]
+    heat: Option<f64>,
     callback: Callback<TileMsg>,
 }
 
 #[function_component(TileView)]
 fn tile_component(props: &TileProps) -> Html {
-    use game::AnyTile::*;
-
     let TileProps {
         x,
         y,
         tile,
         pressed,
         locked,
+        probability,
+        heat,
         callback,
     } = props.clone();
-    let mut class = classes!(
-        "cell",
-        match tile {
-            Closed => classes!(),
-            Open(count) => classes!("open", format!("num-{}", count)),
-            Flag => classes!("flag"),
-            Question => classes!("question"),
-            Exploded => classes!("open", "mine", "oops"),
-            Mine => classes!("open", "mine"),
-            IncorrectFlag => classes!("flag", "wrong"),
-        }
-    );
+    let mut class = classes!("cell", ViewCellState(tile).css_classes());
     if pressed {
         class.push("open");
     }
     if locked {
         class.push("locked");
     }
+    if probability.is_some() {
+        class.push("probability");
+    }
+    if heat.is_some() {
+        class.push("heat");
+    }
+    // the prefix is the one main.scss configures
+    let style = match (probability, heat) {
+        (Some(probability), _) => Some(format!("--dtn-probability: {:.3}", probability)),
+        (None, Some(heat)) => Some(format!("--dtn-heat: {:.3}", heat)),
+        (None, None) => None,
+    };
 
     let onmousedown = {
         let callback = callback.clone();
@@ -114,7 +277,7 @@ fn tile_component(props: &TileProps) -> Html {
                 pos: (x, y),
                 buttons,
             };
-            callback.emit(TileMsg::Update(tile_state));
+            callback.emit(TileMsg::Down(tile_state));
             log::trace!("({}, {}) mouse down ({:?})", x, y, buttons);
         })
     };
@@ -155,18 +318,41 @@ fn tile_component(props: &TileProps) -> Html {
     };
 
     html! {
-        <td {class} {onmousedown} {onmouseup} {onmouseenter} {onmouseleave}/>
+        <td {class} {style} {onmousedown} {onmouseup} {onmouseenter} {onmouseleave}/>
     }
 }
 
+#[derive(Properties, Clone, PartialEq)]
+pub(crate) struct GameProps {
+    /// Seed of the first generated minefield, random if not given
+    #[prop_or_default]
+    pub seed: Option<u64>,
+}
+
 pub(crate) struct GameView {
     settings: settings::Settings,
+    /// What the settings dialog shows and local storage keeps, becomes `settings` when the dialog is closed
+    dialog_settings: Rc<settings::Settings>,
     game: Option<game::Game>,
     seed: u64,
+    /// The game was restored from local storage, the seed it was generated from isn't known
+    restored: bool,
     prev_time: u32,
     settings_open: bool,
     cur_tile_state: Option<TileState>,
+    /// Left and right were down together since the last release, releasing all buttons chords instead
+    chord_press: bool,
+    /// Where and with which button a drag may start, it starts once the pointer reaches another tile, so a press
+    /// released on the same tile is still a click
+    drag_origin: Option<TileState>,
+    /// Tiles are being opened or flagged by dragging, releasing ends the gesture without any other action
+    dragging: bool,
+    /// Mine probability of closed tiles, only computed when the overlay is enabled
+    probabilities: Option<Array2<Option<f64>>>,
+    /// Shown as a heatmap once the game ended
+    reveal_times: Option<RevealTimes>,
     _timer_interval: Interval,
+    _window_listeners: [EventListener; 2],
 }
 
 impl GameView {
@@ -175,21 +361,35 @@ impl GameView {
             game,
             settings,
             seed,
+            restored,
             ..
         } = self;
         game.get_or_insert_with(|| {
-            use game::{MinefieldGenerator, RandomMinefieldGenerator, StartTile};
-            use settings::Generator::*;
-            let minefield = match settings.generator {
-                Random => RandomMinefieldGenerator::new(*seed, coords, StartTile::Random)
-                    .generate(settings.game_config),
-                NoRandom => RandomMinefieldGenerator::new(*seed, coords, StartTile::AlwaysZero)
-                    .generate(settings.game_config),
-            };
-            game::Game::new(minefield)
+            use game::MinefieldGenerator;
+            *restored = false;
+            let minefield = settings
+                .generator
+                .kind()
+                .make(*seed, coords)
+                .generate(settings.game_config);
+            let mut game = game::Game::new(minefield);
+            game.set_chord_ignores_questions(settings.chord_ignores_questions);
+            game
         })
     }
 
+    /// Apply the rules picked in the settings to the current game, a saved game may have been played with others
+    fn apply_rules(&mut self) {
+        if let Some(game) = &mut self.game {
+            game.set_chord_ignores_questions(self.settings.chord_ignores_questions);
+        }
+    }
+
+    /// Seed of the current game, or of the next one if none was started yet
+    fn get_seed(&self) -> Option<u64> {
+        (self.game.is_none() || !self.restored).then_some(self.seed)
+    }
+
     fn get_size(&self) -> game::Ix2 {
         self.game
             .as_ref()
@@ -208,11 +408,18 @@ impl GameView {
         self.game.as_ref().map(|g| g.elapsed_secs(utc_now())).unwrap_or(0)
     }
 
-    fn get_mines_left(&self) -> i32 {
-        self.game
-            .as_ref()
-            .map(|g| g.mines_left() as i32)
-            .unwrap_or(self.get_total_mines() as i32)
+    /// Value of the mine counter, depends on the counter mode setting
+    fn get_mine_counter(&self) -> i32 {
+        use settings::CounterMode::*;
+        match self.settings.counter_mode {
+            MinesLeft => self
+                .game
+                .as_ref()
+                .map(|g| g.mines_left() as i32)
+                .unwrap_or(self.get_total_mines() as i32),
+            FlagsPlaced => self.game.as_ref().map_or(0, |g| g.flag_count() as i32),
+            TotalMines => self.get_total_mines() as i32,
+        }
     }
 
     fn get_game_state(&self) -> game::GameState {
@@ -231,19 +438,12 @@ impl GameView {
         )
     }
 
-    fn get_game_state_class(&self) -> Classes {
+    fn get_view_game_state(&self) -> ViewGameState {
         use game::GameState::*;
-        let mid_open = self.is_mid_open();
-        let game_state = self.get_game_state();
-        classes!(match game_state {
-            NotStarted | InProgress if mid_open => "mid-open",
-            NotStarted => "not-started",
-            InProgress => "in-progress",
-            Win => "win",
-            Lose => "lose",
-            InstantWin => "instant-win",
-            InstantLoss => "instant-loss",
-        })
+        match self.get_game_state() {
+            NotStarted | InProgress if self.is_mid_open() => ViewGameState::MidOpen,
+            game_state => ViewGameState::Game(game_state),
+        }
     }
 
     fn is_playable(&self) -> bool {
@@ -260,25 +460,333 @@ impl GameView {
 
     fn open_tile(&mut self, coords: game::Ix2) -> bool {
         use game::AnyTile::*;
+        let flags_enabled = self.settings.flags_enabled;
         let game = self.get_or_create_game(coords);
+        match game.tile_at(coords) {
+            Closed => game
+                .apply(game::Action::Open(coords), utc_now())
+                .has_update(),
+            Open(_) => GameView::chord_open(game, coords, flags_enabled),
+            _ => false,
+        }
+    }
+
+    /// Chord an open tile, without flags the mines around it are the ones the solver proves
+    fn chord_open(game: &mut game::Game, coords: game::Ix2, flags_enabled: bool) -> bool {
+        use game::Solver;
         let now = utc_now();
+        if flags_enabled {
+            game.chord_open(coords, now).has_update()
+        } else {
+            let solver = game::TrivialSolver.then(game::SubsetSolver);
+            game.chord_open_deduced(&solver, coords, now).has_update()
+        }
+    }
+
+    /// Left click, opens unless the primary action is inverted, open tiles are always chorded
+    fn primary_action(&mut self, coords: game::Ix2) -> bool {
+        let is_open = self
+            .game
+            .as_ref()
+            .is_some_and(|game| game.is_open_at(coords));
+        if self.settings.invert_primary_action && self.settings.flags_enabled && !is_open {
+            self.flag_question(coords)
+        } else {
+            self.open_tile(coords)
+        }
+    }
+
+    /// Right click, flags unless the primary action is inverted
+    fn secondary_action(&mut self, coords: game::Ix2) -> bool {
+        if self.settings.invert_primary_action && self.settings.flags_enabled {
+            self.open_tile(coords)
+        } else {
+            self.flag_question(coords)
+        }
+    }
+
+    /// Open a closed tile passed over while dragging, flagged and open tiles are left alone, nothing opens once the
+    /// game ended so the drag stops at the first explosion
+    fn drag_reveal(&mut self, coords: game::Ix2) -> bool {
+        let game = self.get_or_create_game(coords);
+        game.tile_at(coords) == game::AnyTile::Closed && game.open(coords, utc_now()).has_update()
+    }
+
+    /// Flag a closed tile passed over while dragging, flags are never removed so passing over one twice is harmless
+    fn drag_flag(&mut self, coords: game::Ix2) -> bool {
+        if !self.settings.flags_enabled {
+            return false;
+        }
+        let Some(game) = self.game.as_mut() else {
+            return false;
+        };
+        game.tile_at(coords) == game::AnyTile::Closed && game.flag(coords).has_update()
+    }
+
+    /// Open with the primary button, flag with the secondary one
+    fn drag_action(&mut self, tile_state: TileState) -> bool {
+        match tile_state.buttons {
+            MouseButtons::RIGHT => self.drag_flag(tile_state.pos),
+            _ => self.drag_reveal(tile_state.pos),
+        }
+    }
+
+    /// Track a drag with a single button, returns whether any tile changed
+    fn update_drag(&mut self, tile_state: &TileState) -> bool {
+        let enabled = self.settings.enable_drag_reveal && !self.settings.invert_primary_action;
+        let single_button = matches!(tile_state.buttons, MouseButtons::LEFT | MouseButtons::RIGHT);
+        if !enabled || !single_button {
+            self.drag_origin = None;
+            self.dragging = false;
+            return false;
+        }
+        match self.drag_origin {
+            // a different button is a different gesture
+            Some(origin) if origin.buttons != tile_state.buttons => {
+                self.drag_origin = Some(*tile_state);
+                self.dragging = false;
+                false
+            }
+            Some(origin) if self.dragging || origin.pos != tile_state.pos => {
+                let mut changed = false;
+                if !self.dragging {
+                    log::debug!("drag from: {:?}", origin);
+                    self.dragging = true;
+                    changed |= self.drag_action(origin);
+                }
+                changed | self.drag_action(*tile_state)
+            }
+            Some(_) => false,
+            None => {
+                self.drag_origin = Some(*tile_state);
+                false
+            }
+        }
+    }
+
+    fn chord_tile(&mut self, coords: game::Ix2) -> bool {
+        let flags_enabled = self.settings.flags_enabled;
+        let Some(game) = self.game.as_mut() else {
+            return false;
+        };
         match game.tile_at(coords) {
-            Closed => game.open(coords, now).has_update(),
-            Open(_) => game.chord_open(coords, now).has_update(),
+            game::AnyTile::Open(_) => GameView::chord_open(game, coords, flags_enabled),
             _ => false,
         }
     }
 
     fn flag_question(&mut self, coords: game::Ix2) -> bool {
         use game::AnyTile::*;
+        if !self.settings.flags_enabled {
+            return false;
+        }
         let enable_question_mark = self.settings.enable_question_mark;
         let enable_flag_chord = self.settings.enable_flag_chord;
         let game = self.get_or_create_game(coords);
-        match game.tile_at(coords) {
-            Flag if enable_question_mark => game.flag_question(coords).has_update(),
-            Closed | Flag | Question => game.flag(coords).has_update(),
-            Open(_) if enable_flag_chord => game.chord_flag(coords).has_update(),
-            _ => false,
+        let action = match game.tile_at(coords) {
+            Flag if enable_question_mark => game::Action::FlagQuestion(coords),
+            Closed | Flag | Question => game::Action::Flag(coords),
+            Open(_) if enable_flag_chord => game::Action::ChordFlag(coords),
+            _ => return false,
+        };
+        game.apply(action, utc_now()).has_update()
+    }
+
+    /// Load the game saved for this config, counts that drifted from the board are fixed and anything else corrupted
+    /// discards it
+    fn load_game(slot: &str) -> Option<game::Game> {
+        let mut game: Option<game::Game> = SlotLocalOrDefault::slot_local_or_default(slot);
+        if game.as_mut().is_some_and(game::Game::recompute_counts) {
+            log::warn!("Saved game had wrong counts, recounted them from the board");
+        }
+        game.filter(|game| match game.validate() {
+            Ok(()) => true,
+            Err(err) => {
+                log::warn!("Discarding saved game: {}", err);
+                false
+            }
+        })
+    }
+
+    /// Save the current game into the slot of the current settings, no game clears the slot
+    fn save_game(&self) {
+        let slot = game_slot(&self.settings);
+        self.game.slot_local_save(&slot);
+        self.reveal_times.slot_local_save(&slot);
+    }
+
+    /// Load the reveal times saved in this slot, discarding them if they don't fit the game
+    fn load_reveal_times(slot: &str, game: Option<&game::Game>) -> Option<RevealTimes> {
+        use game::NdConvert;
+        let reveal_times: Option<RevealTimes> = SlotLocalOrDefault::slot_local_or_default(slot);
+        let size: [usize; 2] = game?.size().convert();
+        reveal_times.filter(|reveal_times| reveal_times.0.shape() == size)
+    }
+
+    /// Record when the tiles opened by the last move were opened
+    fn record_reveal_times(&mut self) {
+        let Some(game) = self.game.as_ref() else {
+            return;
+        };
+        self.reveal_times
+            .get_or_insert_with(|| RevealTimes::new(game))
+            .record(game, utc_now());
+    }
+
+    /// Has to be called after every move that changed the board
+    fn on_successful_move(&mut self) {
+        self.record_reveal_times();
+        self.update_probabilities();
+    }
+
+    /// Replace the settings, switching to the save slot of the new settings if it changed
+    fn set_settings(&mut self, settings: settings::Settings) -> bool {
+        if self.settings == settings {
+            return false;
+        }
+        let slot = game_slot(&settings);
+        if game_slot(&self.settings) != slot {
+            self.save_game();
+            self.game = GameView::load_game(&slot);
+            self.reveal_times = GameView::load_reveal_times(&slot, self.game.as_ref());
+            self.restored = self.game.is_some();
+            self.seed = js_random_seed();
+        }
+        self.settings = settings;
+        self.apply_rules();
+        self.update_probabilities();
+        true
+    }
+
+    /// Move to the next config picked from recent results, the game in progress must already be cleared
+    fn adapt_difficulty(&mut self, results: &RecentResults) {
+        let game_config =
+            game::AdaptiveDifficulty::default().next_config(self.settings.game_config, &results.0);
+        let settings = settings::Settings {
+            game_config,
+            ..self.settings.clone()
+        };
+        Rc::make_mut(&mut self.dialog_settings).game_config = game_config;
+        self.dialog_settings.local_save();
+        self.set_settings(settings);
+        // a fresh game is expected, not one left in the adaptive slot of this config
+        self.game = None;
+        self.reveal_times = None;
+    }
+
+    /// Recompute the probability overlay, has to be called whenever the board or the settings change
+    fn update_probabilities(&mut self) {
+        self.probabilities = None;
+        if !self.settings.enable_probability_overlay {
+            return;
+        }
+        let Some(game) = self.game.as_ref().filter(|game| !game.ended()) else {
+            return;
+        };
+        let obs = game::Observation::from_game(game);
+        let fingerprint = obs.fingerprint();
+        let cached: Option<game::AnalysisResult> = LocalOrDefault::local_or_default();
+        let analysis = match cached.filter(|analysis| analysis.fingerprint == fingerprint) {
+            Some(analysis) => analysis,
+            None => {
+                let analysis = game::AnalysisResult::new(&obs);
+                Some(analysis.clone()).local_save();
+                analysis
+            }
+        };
+        let probabilities = analysis.cells().map(|confidence| {
+            use game::CellConfidence::*;
+            match *confidence {
+                ProvenSafe => Some(0.0),
+                ProvenMine => Some(1.0),
+                Probable(probability) => Some(probability),
+                Revealed | Unknown => None,
+            }
+        });
+        self.probabilities = Some(probabilities);
+    }
+
+    /// Handle the buttons pressed over a tile changing, returns whether a re-render is needed
+    fn update_tile_state(&mut self, tile_state: TileState) -> bool {
+        log::trace!("tile update: {:?}", tile_state);
+        if tile_state.buttons.is_empty() {
+            // all mouse buttons were released while in tile_state.pos
+            // we have to figure out which mouse buttons were released
+            let chord_press = std::mem::take(&mut self.chord_press);
+            let dragging = std::mem::take(&mut self.dragging);
+            self.drag_origin = None;
+            match self.cur_tile_state.take() {
+                // nothing to do, mouse is just moving unpressed
+                None => false,
+                // the drag already opened or flagged everything on its way, the whole drag counts as one move
+                Some(_) if dragging => {
+                    log::debug!("drag ended");
+                    self.on_successful_move();
+                    true
+                }
+                // left and right were pressed together at some point, the classic two-button chord
+                Some(TileState { pos, .. }) if chord_press => {
+                    log::debug!("chord tile: {:?}", pos);
+                    if self.chord_tile(pos) {
+                        self.on_successful_move();
+                    }
+                    true
+                }
+                Some(TileState { pos, buttons }) => match buttons {
+                    // only the left button was released, this means we open the tile (or flag if inverted)
+                    MouseButtons::LEFT => {
+                        log::debug!("primary action on tile: {:?}", pos);
+                        if self.primary_action(pos) {
+                            self.on_successful_move();
+                        }
+                        true
+                    }
+                    // only the right button was released, this means we flag the tile (or open if inverted)
+                    MouseButtons::RIGHT => {
+                        log::debug!("secondary action on tile: {:?}", pos);
+                        if self.secondary_action(pos) {
+                            self.on_successful_move();
+                        }
+                        true
+                    }
+                    // otherwise some combination of multiple buttons was released, we treat this as a cancel
+                    // we should update because we might have to visually "unpress" some tiles
+                    _ => {
+                        log::trace!("redraw: multiple buttons changed, maybe redraw");
+                        true
+                    }
+                },
+            }
+        } else {
+            let new_chord_press = start_chord_press(&mut self.chord_press, tile_state.buttons);
+            let drag_revealed = self.update_drag(&tile_state);
+            if drag_revealed {
+                // the overlay waits for the end of the drag, the times can't
+                self.record_reveal_times();
+            }
+            // there's some non-empty button state, we have to update the cur_tile_state, but whether there is
+            // a need for a re-render will depend on whether either the position or the LEFT button state
+            // changed
+            match self.cur_tile_state.replace(tile_state) {
+                _ if new_chord_press => {
+                    log::trace!("redraw: chord press started");
+                    true
+                }
+                _ if drag_revealed => {
+                    log::trace!("redraw: drag opened tiles");
+                    true
+                }
+                None => {
+                    log::trace!("redraw: tile state removed");
+                    true
+                }
+                Some(TileState { pos, buttons }) => {
+                    log::trace!("redraw: maybe new tile states causes changes");
+                    (pos != tile_state.pos)
+                        && ((buttons & MouseButtons::LEFT)
+                            != (tile_state.buttons & MouseButtons::LEFT))
+                }
+            }
         }
     }
 
@@ -287,6 +795,30 @@ impl GameView {
         Interval::new(500, move || link.send_message(Msg::UpdateTime))
     }
 
+    /// Releases outside of the board (or the window) and losing focus never reach the tiles, without these listeners a
+    /// tile could stay pressed. This is `mouseup` rather than `pointerup` because `pointerup` fires before the tile's
+    /// `mouseup`, the cancel has to come after it.
+    fn create_window_listeners(ctx: &Context<Self>) -> [EventListener; 2] {
+        use wasm_bindgen::JsCast;
+        let window = gloo::utils::window();
+        let mouseup = {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "mouseup", move |e| {
+                let all_released = e.dyn_ref::<MouseEvent>().is_some_and(|e| e.buttons() == 0);
+                if all_released {
+                    link.send_message(Msg::CancelPress);
+                }
+            })
+        };
+        let blur = {
+            let link = ctx.link().clone();
+            EventListener::new(&window, "blur", move |_| {
+                link.send_message(Msg::CancelPress)
+            })
+        };
+        [mouseup, blur]
+    }
+
     fn is_pressed(&self, coords: game::Ix2, tile: game::AnyTile) -> bool {
         use game::AnyTile::*;
         if self.get_game_state().is_final() {
@@ -315,6 +847,13 @@ impl GameView {
                 .game
                 .as_ref()
                 .map_or(false, |game| game.is_chordable(pos)),
+            (Some(TileState { pos, .. }), Closed)
+                if self.chord_press && is_neighbor(pos, coords) =>
+            {
+                self.game
+                    .as_ref()
+                    .is_some_and(|game| game.is_chordable(pos))
+            }
             _ => false,
         }
     }
@@ -322,18 +861,33 @@ impl GameView {
 
 impl Component for GameView {
     type Message = Msg;
-    type Properties = ();
+    type Properties = GameProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        Self {
-            settings: LocalOrDefault::local_or_default(),
-            game: LocalOrDefault::local_or_default(),
-            seed: js_random_seed(),
+        let settings: settings::Settings = LocalOrDefault::local_or_default();
+        let slot = game_slot(&settings);
+        let game = GameView::load_game(&slot);
+        let reveal_times = GameView::load_reveal_times(&slot, game.as_ref());
+        let mut view = Self {
+            dialog_settings: Rc::new(settings.clone()),
+            settings,
+            restored: game.is_some(),
+            game,
+            seed: ctx.props().seed.unwrap_or_else(js_random_seed),
             prev_time: 0,
             settings_open: false,
             cur_tile_state: None,
+            chord_press: false,
+            drag_origin: None,
+            dragging: false,
+            probabilities: None,
+            reveal_times,
             _timer_interval: GameView::create_timer(ctx),
-        }
+            _window_listeners: GameView::create_window_listeners(ctx),
+        };
+        view.apply_rules();
+        view.update_probabilities();
+        view
     }
 
     fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
@@ -341,56 +895,32 @@ impl Component for GameView {
         use TileMsg::*;
 
         let updated = match msg {
+            TileEvent(Down(tile_state)) => {
+                if self.cur_tile_state.is_none() {
+                    // a fresh press, any gesture from a release outside the board is stale
+                    self.chord_press = false;
+                    self.drag_origin = None;
+                    self.dragging = false;
+                }
+                self.update_tile_state(tile_state)
+            }
             TileEvent(Leave) => {
                 log::trace!("tile leave");
                 self.cur_tile_state.take().is_some()
             }
-            TileEvent(Update(tile_state)) => {
-                log::trace!("tile update: {:?}", tile_state);
-                if tile_state.buttons.is_empty() {
-                    // all mouse buttons were released while in tile_state.pos
-                    // we have to figure out which mouse buttons were released
-                    match self.cur_tile_state.take() {
-                        // nothing to do, mouse is just moving unpressed
-                        None => false,
-                        Some(TileState { pos, buttons }) => match buttons {
-                            // only the left button was released, this means we open the tile
-                            MouseButtons::LEFT => {
-                                log::debug!("open tile: {:?}", pos);
-                                self.open_tile(pos);
-                                true
-                            }
-                            // only the right button was released, this means we flag the tile
-                            MouseButtons::RIGHT => {
-                                log::debug!("flag tile: {:?}", pos);
-                                self.flag_question(pos);
-                                true
-                            }
-                            // otherwise some combination of multiple buttons was released, we treat this as a cancel
-                            // we should update because we might have to visually "unpress" some tiles
-                            _ => {
-                                log::trace!("redraw: multiple buttons changed, maybe redraw");
-                                true
-                            }
-                        },
-                    }
-                } else {
-                    // there's some non-empty button state, we have to update the cur_tile_state, but whether there is
-                    // a need for a re-render will depend on whether either the position or the LEFT button state
-                    // changed
-                    match self.cur_tile_state.replace(tile_state.clone()) {
-                        None => {
-                            log::trace!("redraw: tile state removed");
-                            true
-                        }
-                        Some(TileState { pos, buttons }) => {
-                            log::trace!("redraw: maybe new tile states causes changes");
-                            (pos != tile_state.pos)
-                                && ((buttons & MouseButtons::LEFT)
-                                    != (tile_state.buttons & MouseButtons::LEFT))
-                        }
-                    }
+            TileEvent(Update(tile_state)) => self.update_tile_state(tile_state),
+            CancelPress => {
+                self.chord_press = false;
+                self.drag_origin = None;
+                let dragging = std::mem::take(&mut self.dragging);
+                if dragging {
+                    self.on_successful_move();
                 }
+                let pressed = self.cur_tile_state.take().is_some();
+                if pressed {
+                    log::trace!("press cancelled outside of the board");
+                }
+                pressed || dragging
             }
             UpdateTime => {
                 let time = self.get_time();
@@ -403,49 +933,112 @@ impl Component for GameView {
             }
             NewGame => {
                 self.seed = js_random_seed();
-                self.game.take().map_or(false, |_| true)
+                self.probabilities = None;
+                self.reveal_times = None;
+                let game = self.game.take();
+                if let Some(game) = game.as_ref().filter(|game| game.ended()) {
+                    let mut results: RecentResults = LocalOrDefault::local_or_default();
+                    use game::GameState::*;
+                    results.push(matches!(game.cur_state(), Win | InstantWin));
+                    results.local_save();
+                    if self.settings.enable_adaptive_difficulty {
+                        self.adapt_difficulty(&results);
+                    }
+                }
+                game.is_some()
+            }
+            ClearFlags => {
+                let cleared = self
+                    .game
+                    .as_mut()
+                    .is_some_and(|game| game.clear_flags().has_update());
+                if cleared {
+                    self.update_probabilities();
+                }
+                cleared
+            }
+            ClearQuestions => self
+                .game
+                .as_mut()
+                .is_some_and(|game| game.clear_questions().has_update()),
+            FlagProvenMines => {
+                use game::Solver;
+                let solver = game::TrivialSolver.then(game::SubsetSolver);
+                let flagged = self
+                    .game
+                    .as_mut()
+                    .is_some_and(|game| game.flag_proven_mines(&solver).has_update());
+                if flagged {
+                    self.update_probabilities();
+                }
+                flagged
             }
             ToggleSettings => {
                 self.settings_open = !self.settings_open;
                 if !self.settings_open {
-                    self.settings = LocalOrDefault::local_or_default();
+                    self.set_settings((*self.dialog_settings).clone());
                 }
                 true
             }
-            UpdateSettings(settings) => {
-                if self.settings != settings {
-                    self.settings = settings;
-                    true
-                } else {
-                    false
+            UpdateSettings(settings) => self.set_settings(settings),
+            DispatchSettings(action) => {
+                self.dialog_settings = self.dialog_settings.clone().reduce(action);
+                self.dialog_settings.local_save();
+                if action.is_immediate() {
+                    let settings = Rc::new(self.settings.clone()).reduce(action);
+                    self.set_settings(Rc::unwrap_or_clone(settings));
                 }
+                true
             }
         };
-        self.game.local_save();
+        self.save_game();
         updated
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        use game::NdConvert;
         use settings::SettingsView;
         use Msg::*;
 
         let (cols, rows) = self.get_size();
-        let game_state_class = classes!(self.get_game_state_class());
+        let game_state_class = self.get_view_game_state().css_class();
         let is_playable = self.is_playable();
-        let mines_left = format_for_counter(self.get_mines_left());
+        let mine_counter = format_for_counter(self.get_mine_counter());
         let elapsed_time = format_for_counter(self.get_time() as i32);
         let cb_new_game = ctx.link().callback(|e: MouseEvent| {
             e.stop_propagation();
             NewGame
         });
         let cb_show_settings = ctx.link().callback(|_| ToggleSettings);
+        let cb_toggle_primary_action = ctx
+            .link()
+            .callback(|_| DispatchSettings(settings::SettingsAction::TogglePrimaryAction));
+        let cb_cycle_counter_mode = ctx
+            .link()
+            .callback(|_| DispatchSettings(settings::SettingsAction::CycleCounterMode));
+        let cb_dispatch_settings = ctx.link().callback(DispatchSettings);
+        let cb_clear_flags = ctx.link().callback(|_| ClearFlags);
+        let cb_clear_questions = ctx.link().callback(|_| ClearQuestions);
+        let cb_flag_proven_mines = ctx.link().callback(|_| FlagProvenMines);
+        let reveal_times = self
+            .reveal_times
+            .as_ref()
+            .filter(|_| self.get_game_state().is_final());
 
         html! {
             <div class="detonito" oncontextmenu={Callback::from(move |e: MouseEvent| e.prevent_default())}>
                 <small onclick={cb_show_settings}>{"···"}</small>
                 <nav>
-                    <aside>{mines_left}</aside>
-                    <span><button class={game_state_class} onclick={cb_new_game}/></span>
+                    <aside onclick={cb_cycle_counter_mode}>{mine_counter}</aside>
+                    <span>
+                        <button class={game_state_class} onclick={cb_new_game}/>
+                        if self.settings.flags_enabled {
+                            <button class={classes!("mode", if self.settings.invert_primary_action { "flag" } else { "dig" })} onclick={cb_toggle_primary_action}/>
+                        }
+                        if self.settings.enable_flag_proven_mines && self.settings.flags_enabled {
+                            <button class={classes!("flag-proven")} onclick={cb_flag_proven_mines}/>
+                        }
+                    </span>
                     <aside>{elapsed_time}</aside>
                 </nav>
                 <table class={is_playable.then_some("playable")}>
@@ -458,9 +1051,11 @@ impl Component for GameView {
                                         let tile = self.game.as_ref().map_or(game::AnyTile::Closed, |game| game.tile_at(pos));
                                         let locked = self.game.as_ref().map_or(false, |game| !game.is_tile_playable(pos));
                                         let pressed = self.is_pressed(pos, tile);
+                                        let probability = self.probabilities.as_ref().and_then(|probabilities| probabilities[pos.convert()]);
+                                        let heat = reveal_times.and_then(|reveal_times| reveal_times.heat(pos));
                                         let callback = ctx.link().callback(Msg::TileEvent);
                                         html! {
-                                            <TileView {x} {y} {tile} {callback} {pressed} {locked}/>
+                                            <TileView {x} {y} {tile} {callback} {pressed} {locked} {probability} {heat}/>
                                         }
                                     })
                                 }
@@ -468,8 +1063,101 @@ impl Component for GameView {
                         })
                     }
                 </table>
-                <SettingsView open={self.settings_open}/>
+                <SettingsView open={self.settings_open} settings={self.dialog_settings.clone()} ondispatch={cb_dispatch_settings} seed={self.get_seed()} onclearflags={cb_clear_flags} onclearquestions={cb_clear_questions}/>
             </div>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+    use game::MinefieldGenerator;
+
+    #[test]
+    fn adaptive_games_have_their_own_slots() {
+        let manual = settings::Settings::default();
+        let adaptive = settings::Settings {
+            enable_adaptive_difficulty: true,
+            ..manual.clone()
+        };
+        assert_eq!(game_slot(&manual), "9x9:10");
+        assert_ne!(game_slot(&adaptive), game_slot(&manual));
+        assert!(game_slot(&adaptive).ends_with(&game_slot(&manual)));
+    }
+
+    #[test]
+    fn both_buttons_start_a_chord() {
+        let mut chord_press = false;
+        assert!(!start_chord_press(&mut chord_press, MouseButtons::LEFT));
+        assert!(start_chord_press(
+            &mut chord_press,
+            MouseButtons::LEFT | MouseButtons::RIGHT
+        ));
+        // releasing one button keeps the chord for the final release
+        assert!(!start_chord_press(&mut chord_press, MouseButtons::RIGHT));
+        assert!(chord_press);
+        assert!(std::mem::take(&mut chord_press));
+        assert!(!start_chord_press(&mut chord_press, MouseButtons::RIGHT));
+    }
+
+    #[test]
+    fn game_state_classes() {
+        use game::GameState::*;
+
+        assert_eq!(ViewGameState::MidOpen.css_class(), "mid-open");
+        for (state, class) in [
+            (NotStarted, "not-started"),
+            (InProgress, "in-progress"),
+            (Win, "win"),
+            (Lose, "lose"),
+            (InstantWin, "instant-win"),
+            (InstantLoss, "instant-loss"),
+        ] {
+            assert_eq!(ViewGameState::Game(state).css_class(), class);
+        }
+    }
+
+    #[test]
+    fn cell_state_classes() {
+        use game::AnyTile::*;
+
+        let empty: &[&str] = &[];
+        assert_eq!(ViewCellState(Closed).css_classes(), empty);
+        assert_eq!(ViewCellState(Open(0)).css_classes(), ["open", "num-0"]);
+        assert_eq!(ViewCellState(Open(8)).css_classes(), ["open", "num-8"]);
+        assert_eq!(ViewCellState(Flag).css_classes(), ["flag"]);
+        assert_eq!(ViewCellState(Question).css_classes(), ["question"]);
+        assert_eq!(
+            ViewCellState(Exploded).css_classes(),
+            ["open", "mine", "oops"]
+        );
+        assert_eq!(ViewCellState(Mine).css_classes(), ["open", "mine"]);
+        assert_eq!(
+            ViewCellState(IncorrectFlag).css_classes(),
+            ["flag", "wrong"]
+        );
+    }
+
+    #[test]
+    fn reveal_times_differ_per_move() {
+        let minefield = game::RandomMinefieldGenerator::new(1, (0, 0), game::StartTile::AlwaysZero)
+            .generate(game::GameConfig::BEGINNER);
+        let mut game = game::Game::new(minefield);
+        game.set_flood_fill(false);
+        let mut reveal_times = RevealTimes::new(&game);
+        let start = DateTime::from_timestamp(0, 0).unwrap();
+        game.open((0, 0), start).unwrap();
+        assert!(reveal_times.record(&game, start));
+
+        let later = start + TimeDelta::milliseconds(1500);
+        game.open((1, 1), later).unwrap();
+        assert!(reveal_times.record(&game, later));
+        assert!(!reveal_times.record(&game, later));
+        assert_eq!(reveal_times.0[[0, 0]], Some(0));
+        assert_eq!(reveal_times.0[[1, 1]], Some(1500));
+        assert_eq!(reveal_times.heat((0, 0)), Some(0.0));
+        assert_eq!(reveal_times.heat((1, 1)), Some(1.0));
+    }
+}
@@ -13,7 +13,7 @@ struct Args {
     #[command(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
 
-    /// Force a seed instead of random
+    /// Force the seed of the first board instead of random, a number or any passphrase
     #[arg(short, long)]
     seed: Option<String>,
 }
@@ -45,5 +45,8 @@ pub fn run_app() {
         .expect("Could not find id=\"game\" element");
 
     log::debug!("App started");
-    yew::Renderer::<game::GameView>::with_root(root).render();
+    let props = game::GameProps {
+        seed: args.seed.as_deref().map(detonito_core::seed_from_str),
+    };
+    yew::Renderer::<game::GameView>::with_root_and_props(root, props).render();
 }
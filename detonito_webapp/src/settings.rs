@@ -4,60 +4,222 @@ use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 use yew::prelude::*;
 
-pub const BEGINNER: game::GameConfig = game::GameConfig::new_unchecked((9, 9), 10);
-pub const INTERMEDIATE: game::GameConfig = game::GameConfig::new_unchecked((16, 16), 40);
-pub const EXPERT: game::GameConfig = game::GameConfig::new_unchecked((30, 16), 99);
-pub const EVIL: game::GameConfig = game::GameConfig::new_unchecked((30, 20), 130);
+pub const BEGINNER: game::GameConfig = game::GameConfig::BEGINNER;
+pub const INTERMEDIATE: game::GameConfig = game::GameConfig::INTERMEDIATE;
+pub const EXPERT: game::GameConfig = game::GameConfig::EXPERT;
+pub const EVIL: game::GameConfig = game::GameConfig::EVIL;
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Generator {
     /// Purely random, even the first tile can have a bomb, that's unlucky
     Random,
+    /// First tile is never a bomb, but it can be any number
+    SafeStart,
     /// First tile is always zero (when possible), in the future this will guaranteed a solvable game
     NoRandom,
     // TODO: NoGuess where guesses are guaranteed losses
 }
 
+impl Generator {
+    pub fn kind(self) -> game::GeneratorKind {
+        match self {
+            Generator::Random => game::GeneratorKind::Random,
+            Generator::SafeStart => game::GeneratorKind::SafeStart,
+            Generator::NoRandom => game::GeneratorKind::ZeroStart,
+        }
+    }
+}
+
+/// Bundles of the rule toggles, picked from the dialog instead of setting each one
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Preset {
+    /// Question marks on, no flag chord or auto opening, and a safe first tile that can be any number
+    Classic,
+    /// What [`Settings::default`] uses, flag chord and auto opening with a zero first tile
+    Modern,
+    /// A zero first tile and nothing else
+    Minimal,
+}
+
+impl Preset {
+    /// Class of the dialog button
+    pub fn class(self) -> &'static str {
+        match self {
+            Preset::Classic => "preset-classic",
+            Preset::Modern => "preset-modern",
+            Preset::Minimal => "preset-minimal",
+        }
+    }
+
+    /// Question mark, flag chord, auto trivial and generator, in that order
+    const fn values(self) -> (bool, bool, bool, Generator) {
+        match self {
+            Preset::Classic => (true, false, false, Generator::SafeStart),
+            Preset::Modern => (false, true, true, Generator::NoRandom),
+            Preset::Minimal => (false, false, false, Generator::NoRandom),
+        }
+    }
+
+    pub fn apply(self, settings: &mut Settings) {
+        let (question_mark, flag_chord, auto_trivial, generator) = self.values();
+        settings.enable_question_mark = question_mark;
+        settings.enable_flag_chord = flag_chord;
+        settings.enable_auto_trivial = auto_trivial;
+        settings.generator = generator;
+    }
+
+    /// Whether the settings have the values of this preset, other settings are not compared
+    pub fn is_applied(self, settings: &Settings) -> bool {
+        self.values()
+            == (
+                settings.enable_question_mark,
+                settings.enable_flag_chord,
+                settings.enable_auto_trivial,
+                settings.generator,
+            )
+    }
+}
+
+/// What the counter left of the state button shows, clicking it moves to the next one
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) enum CounterMode {
+    /// Mines minus flags, can go negative
+    #[default]
+    MinesLeft,
+    FlagsPlaced,
+    TotalMines,
+}
+
+impl CounterMode {
+    pub fn next(self) -> Self {
+        use CounterMode::*;
+        match self {
+            MinesLeft => FlagsPlaced,
+            FlagsPlaced => TotalMines,
+            TotalMines => MinesLeft,
+        }
+    }
+}
+
+/// Missing fields take their value from [`Settings::default`], so settings saved by older versions keep what they have
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub(crate) struct Settings {
+    #[serde(deserialize_with = "deserialize_game_config")]
     pub game_config: game::GameConfig,
     pub generator: Generator,
     pub enable_question_mark: bool,
     pub enable_flag_chord: bool,
+    /// Without flags chording counts the mines the solver proves instead, for players who never flag
+    pub flags_enabled: bool,
     pub enable_auto_trivial: bool,
+    /// Tint closed tiles by their mine probability, this is a strong assist
+    pub enable_probability_overlay: bool,
+    /// Primary click flags and secondary click opens, toggled from the nav for touch players
+    pub invert_primary_action: bool,
+    /// Change the mine count on each new game depending on recent results
+    pub enable_adaptive_difficulty: bool,
+    /// Dragging with the primary button opens every closed tile passed over, with the secondary button flags them
+    pub enable_drag_reveal: bool,
+    /// Show a button that flags every tile the solver proves to be a mine
+    pub enable_flag_proven_mines: bool,
+    pub counter_mode: CounterMode,
+    /// Chording opens question marked neighbors instead of being blocked by them
+    pub chord_ignores_questions: bool,
 }
 
 impl Settings {
     const MAX_SIZE: game::Ix = 99;
+
+    /// Bring a config back within what the dialog can reach, older versions or edits to local storage can leave no
+    /// safe tile, which would only get clamped on the next change
+    fn normalize_game_config(game_config: game::GameConfig) -> game::GameConfig {
+        let (size_x, size_y) = game_config.size;
+        let normalized = game::GameConfig::new(
+            (size_x.min(Self::MAX_SIZE), size_y.min(Self::MAX_SIZE)),
+            game_config.mines,
+        );
+        if normalized == game_config {
+            return game_config;
+        }
+        log::warn!(
+            "Invalid game config {:?}, using {:?} instead",
+            game_config,
+            normalized
+        );
+        normalized
+    }
 }
 
+fn deserialize_game_config<'de, D>(deserializer: D) -> Result<game::GameConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    game::GameConfig::deserialize(deserializer).map(Settings::normalize_game_config)
+}
+
+// any size reachable from the settings must be accepted by the core
+const _: () = assert!(
+    game::mult(Settings::MAX_SIZE, Settings::MAX_SIZE) <= game::GameConfig::MAX_TOTAL_TILES
+);
+
 impl Default for Settings {
     fn default() -> Self {
+        let (enable_question_mark, enable_flag_chord, enable_auto_trivial, generator) =
+            Preset::Modern.values();
         Self {
             game_config: BEGINNER,
-            generator: Generator::NoRandom,
-            enable_question_mark: false,
-            enable_flag_chord: true,
-            enable_auto_trivial: true,
+            generator,
+            enable_question_mark,
+            enable_flag_chord,
+            flags_enabled: true,
+            enable_auto_trivial,
+            enable_probability_overlay: false,
+            invert_primary_action: false,
+            enable_adaptive_difficulty: false,
+            enable_drag_reveal: false,
+            enable_flag_proven_mines: false,
+            counter_mode: CounterMode::MinesLeft,
+            chord_ignores_questions: false,
         }
     }
 }
 
 impl StorageKey for Settings {
-    const KEY: &'static str = "detonito:settings";
+    const KEY: &'static str = "detonito:settings:v2";
+    const LEGACY_KEYS: &'static [&'static str] = &["detonito:settings"];
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum SettingsAction {
     ToggleMarkQuestion,
+    ToggleFlags,
+    ToggleProbabilityOverlay,
+    ToggleAdaptiveDifficulty,
+    ToggleDragReveal,
+    ToggleFlagProvenMines,
+    ToggleChordIgnoresQuestions,
     SetGameConfig(game::GameConfig),
     SetGenerator(Generator),
+    ApplyPreset(Preset),
     IncreaseSizeX,
     DecreaseSizeX,
     IncreaseSizeY,
     DecreaseSizeY,
     IncreaseMines,
     DecreaseMines,
+    /// From the nav, swaps what clicking a closed tile does
+    TogglePrimaryAction,
+    /// From the nav, see [`CounterMode::next`]
+    CycleCounterMode,
+}
+
+impl SettingsAction {
+    /// Whether the action comes from the nav and applies to the game right away, the others apply once the dialog
+    /// is closed
+    pub const fn is_immediate(self) -> bool {
+        matches!(self, Self::TogglePrimaryAction | Self::CycleCounterMode)
+    }
 }
 
 impl Reducible for Settings {
@@ -70,12 +232,33 @@ impl Reducible for Settings {
             ToggleMarkQuestion => {
                 settings.enable_question_mark = !settings.enable_question_mark;
             }
+            ToggleFlags => {
+                settings.flags_enabled = !settings.flags_enabled;
+            }
+            ToggleProbabilityOverlay => {
+                settings.enable_probability_overlay = !settings.enable_probability_overlay;
+            }
+            ToggleAdaptiveDifficulty => {
+                settings.enable_adaptive_difficulty = !settings.enable_adaptive_difficulty;
+            }
+            ToggleDragReveal => {
+                settings.enable_drag_reveal = !settings.enable_drag_reveal;
+            }
+            ToggleFlagProvenMines => {
+                settings.enable_flag_proven_mines = !settings.enable_flag_proven_mines;
+            }
+            ToggleChordIgnoresQuestions => {
+                settings.chord_ignores_questions = !settings.chord_ignores_questions;
+            }
             SetGameConfig(game_config) => {
                 settings.game_config = game_config;
             }
             SetGenerator(generator) => {
                 settings.generator = generator;
             }
+            ApplyPreset(preset) => {
+                preset.apply(&mut settings);
+            }
             IncreaseSizeX => {
                 settings.game_config.size.0 =
                     (settings.game_config.size.0 + 1).clamp(1, Settings::MAX_SIZE);
@@ -83,10 +266,8 @@ impl Reducible for Settings {
             DecreaseSizeX => {
                 settings.game_config.size.0 =
                     (settings.game_config.size.0 - 1).clamp(1, Settings::MAX_SIZE);
-                settings.game_config.mines = settings
-                    .game_config
-                    .mines
-                    .clamp(1, settings.game_config.total_tiles());
+                settings.game_config =
+                    game::GameConfig::new(settings.game_config.size, settings.game_config.mines);
             }
             IncreaseSizeY => {
                 settings.game_config.size.1 =
@@ -95,21 +276,28 @@ impl Reducible for Settings {
             DecreaseSizeY => {
                 settings.game_config.size.1 =
                     (settings.game_config.size.1 - 1).clamp(1, Settings::MAX_SIZE);
-                settings.game_config.mines = settings
-                    .game_config
-                    .mines
-                    .clamp(1, settings.game_config.total_tiles());
+                settings.game_config =
+                    game::GameConfig::new(settings.game_config.size, settings.game_config.mines);
             }
             IncreaseMines => {
-                settings.game_config.mines =
-                    (settings.game_config.mines + 1).clamp(1, settings.game_config.total_tiles());
+                settings.game_config = game::GameConfig::new(
+                    settings.game_config.size,
+                    settings.game_config.mines + 1,
+                );
             }
             DecreaseMines => {
-                settings.game_config.mines =
-                    (settings.game_config.mines - 1).clamp(1, settings.game_config.total_tiles());
+                settings.game_config = game::GameConfig::new(
+                    settings.game_config.size,
+                    settings.game_config.mines.saturating_sub(1),
+                );
+            }
+            TogglePrimaryAction => {
+                settings.invert_primary_action = !settings.invert_primary_action;
+            }
+            CycleCounterMode => {
+                settings.counter_mode = settings.counter_mode.next();
             }
         }
-        settings.local_save();
         settings.into()
     }
 }
@@ -118,13 +306,24 @@ impl Reducible for Settings {
 pub(crate) struct SettingsProps {
     #[prop_or_default]
     pub open: bool,
+    /// What the dialog shows, changed by the owner when it gets `ondispatch`
+    pub settings: Rc<Settings>,
+    #[prop_or_default]
+    pub ondispatch: Callback<SettingsAction>,
+    /// Seed of the current board, shown so players can report it
+    #[prop_or_default]
+    pub seed: Option<u64>,
+    #[prop_or_default]
+    pub onclearflags: Callback<MouseEvent>,
+    #[prop_or_default]
+    pub onclearquestions: Callback<MouseEvent>,
 }
 
 #[function_component]
 pub(crate) fn SettingsView(props: &SettingsProps) -> Html {
     use crate::theme::Theme;
 
-    let settings: UseReducerHandle<Settings> = use_reducer_eq(LocalOrDefault::local_or_default);
+    let settings = &props.settings;
     let theme: UseStateHandle<Option<Theme>> = use_state_eq(LocalOrDefault::local_or_default);
 
     let set_theme_light = {
@@ -155,68 +354,113 @@ pub(crate) fn SettingsView(props: &SettingsProps) -> Html {
     };
 
     let set_generator_random = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGenerator(Generator::Random))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGenerator(Generator::Random))
+    };
+
+    let set_generator_safe_start = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGenerator(Generator::SafeStart))
     };
 
     let set_generator_puzzle = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGenerator(Generator::NoRandom))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGenerator(Generator::NoRandom))
+    };
+
+    let preset_button = |preset: Preset| {
+        let onclick = {
+            let ondispatch = props.ondispatch.clone();
+            move |_| ondispatch.emit(SettingsAction::ApplyPreset(preset))
+        };
+        html! {
+            <button class={classes!(preset.class(), preset.is_applied(settings).then_some("pressed"))} {onclick}/>
+        }
     };
 
     let toggle_question = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::ToggleMarkQuestion)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleMarkQuestion)
+    };
+
+    let toggle_flags = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleFlags)
+    };
+
+    let toggle_chord_question = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleChordIgnoresQuestions)
+    };
+
+    let toggle_probability = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleProbabilityOverlay)
+    };
+
+    let toggle_adaptive = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleAdaptiveDifficulty)
+    };
+
+    let toggle_drag = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleDragReveal)
+    };
+
+    let toggle_flag_proven = {
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::ToggleFlagProvenMines)
     };
 
     let inc_mines = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::IncreaseMines)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::IncreaseMines)
     };
 
     let dec_mines = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::DecreaseMines)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::DecreaseMines)
     };
 
     let inc_size_x = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::IncreaseSizeX)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::IncreaseSizeX)
     };
 
     let dec_size_x = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::DecreaseSizeX)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::DecreaseSizeX)
     };
 
     let inc_size_y = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::IncreaseSizeY)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::IncreaseSizeY)
     };
 
     let dec_size_y = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::DecreaseSizeY)
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::DecreaseSizeY)
     };
 
     let set_diff_beginner = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGameConfig(BEGINNER))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGameConfig(BEGINNER))
     };
 
     let set_diff_intermediate = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGameConfig(INTERMEDIATE))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGameConfig(INTERMEDIATE))
     };
 
     let set_diff_expert = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGameConfig(EXPERT))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGameConfig(EXPERT))
     };
 
     let set_diff_evil = {
-        let settings = settings.clone();
-        move |_| settings.dispatch(SettingsAction::SetGameConfig(EVIL))
+        let ondispatch = props.ondispatch.clone();
+        move |_| ondispatch.emit(SettingsAction::SetGameConfig(EVIL))
     };
 
     html! {
@@ -257,15 +501,167 @@ pub(crate) fn SettingsView(props: &SettingsProps) -> Html {
             {format!(" {} × ", settings.game_config.mines)}
             <button class={classes!("mine", "pressed", "locked")}/>
             <hr/>
+            {preset_button(Preset::Classic)}
+            {" "}
+            {preset_button(Preset::Modern)}
+            {" "}
+            {preset_button(Preset::Minimal)}
+            <hr/>
             <button class="locked"/>
             {" "}
-            <button class={classes!("flag", "locked")}/>
+            <button class={classes!("flag", (!settings.flags_enabled).then_some("pressed"))} onclick={toggle_flags}/>
             {" "}
             <button class={classes!("question", (!settings.enable_question_mark).then_some("pressed"))} onclick={toggle_question}/>
+            {" "}
+            <button class={classes!("chord-question", settings.chord_ignores_questions.then_some("pressed"))} onclick={toggle_chord_question}/>
             <hr/>
             <button class={classes!("random", (settings.generator == Generator::Random).then_some("pressed"))} onclick={set_generator_random}/>
             {" "}
+            <button class={classes!("safe-start", (settings.generator == Generator::SafeStart).then_some("pressed"))} onclick={set_generator_safe_start}/>
+            {" "}
             <button class={classes!("puzzle", (settings.generator == Generator::NoRandom).then_some("pressed"))} onclick={set_generator_puzzle}/>
+            <hr/>
+            <button class={classes!("clear", "locked")}/>
+            {" "}
+            <button class={classes!("flag")} onclick={props.onclearflags.clone()}/>
+            {" "}
+            <button class={classes!("question")} onclick={props.onclearquestions.clone()}/>
+            <hr/>
+            <button class={classes!("probability", settings.enable_probability_overlay.then_some("pressed"))} onclick={toggle_probability}/>
+            {" "}
+            <button class={classes!("adaptive", settings.enable_adaptive_difficulty.then_some("pressed"))} onclick={toggle_adaptive}/>
+            {" "}
+            <button class={classes!("drag", settings.enable_drag_reveal.then_some("pressed"))} onclick={toggle_drag}/>
+            {" "}
+            <button class={classes!("flag-proven", settings.enable_flag_proven_mines.then_some("pressed"))} onclick={toggle_flag_proven}/>
+            <hr/>
+            {"seed "}
+            <output class="seed">{props.seed.map_or_else(|| "unknown".to_string(), |seed| format!("{:016x}", seed))}</output>
         </dialog>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_settings_keep_their_fields() {
+        // saved before the overlay, adaptive difficulty and drag settings existed
+        let old = serde_json::json!({
+            "game_config": EXPERT,
+            "generator": "Random",
+            "enable_question_mark": true,
+            "enable_flag_chord": false,
+            "enable_auto_trivial": true,
+        });
+        let settings: Settings = serde_json::from_value(old).unwrap();
+        assert_eq!(
+            settings,
+            Settings {
+                game_config: EXPERT,
+                generator: Generator::Random,
+                enable_question_mark: true,
+                enable_flag_chord: false,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn nav_actions_go_through_the_reducer() {
+        let settings = Rc::new(Settings::default());
+        let settings = settings.reduce(SettingsAction::TogglePrimaryAction);
+        assert!(settings.invert_primary_action);
+        let settings = settings.reduce(SettingsAction::CycleCounterMode);
+        assert_eq!(settings.counter_mode, CounterMode::FlagsPlaced);
+        // the dialog's other changes don't undo them
+        let settings = settings.reduce(SettingsAction::ToggleFlags);
+        assert!(settings.invert_primary_action);
+        assert_eq!(settings.counter_mode, CounterMode::FlagsPlaced);
+
+        assert!(SettingsAction::TogglePrimaryAction.is_immediate());
+        assert!(SettingsAction::CycleCounterMode.is_immediate());
+        assert!(!SettingsAction::ToggleFlags.is_immediate());
+    }
+
+    #[test]
+    fn counter_mode_cycles() {
+        use CounterMode::*;
+
+        assert_eq!(CounterMode::default(), MinesLeft);
+        assert_eq!(MinesLeft.next(), FlagsPlaced);
+        assert_eq!(FlagsPlaced.next(), TotalMines);
+        assert_eq!(TotalMines.next(), MinesLeft);
+    }
+
+    #[test]
+    fn too_many_mines_are_normalized() {
+        let saved = serde_json::json!({
+            "game_config": game::GameConfig::new_unchecked((5, 5), 30),
+        });
+        let settings: Settings = serde_json::from_value(saved).unwrap();
+        assert!(settings.game_config.is_valid());
+        assert_eq!(
+            settings.game_config,
+            game::GameConfig::new_unchecked((5, 5), 24)
+        );
+
+        let saved = serde_json::json!({
+            "game_config": game::GameConfig::new_unchecked((5, 5), 25),
+        });
+        let settings: Settings = serde_json::from_value(saved).unwrap();
+        assert_eq!(
+            settings.game_config,
+            game::GameConfig::new_unchecked((5, 5), 24)
+        );
+    }
+
+    #[test]
+    fn mine_buttons_leave_a_safe_tile() {
+        let mut settings = Rc::new(Settings {
+            game_config: game::GameConfig::new_unchecked((2, 1), 1),
+            ..Default::default()
+        });
+        settings = settings.reduce(SettingsAction::IncreaseMines);
+        assert_eq!(settings.game_config.mines, 1);
+        settings = settings.reduce(SettingsAction::DecreaseSizeX);
+        assert_eq!(
+            settings.game_config,
+            game::GameConfig::new_unchecked((1, 1), 0)
+        );
+        settings = settings.reduce(SettingsAction::DecreaseMines);
+        assert!(settings.game_config.is_valid());
+    }
+
+    #[test]
+    fn modern_preset_values() {
+        let mut settings = Settings {
+            enable_question_mark: true,
+            enable_flag_chord: false,
+            enable_auto_trivial: false,
+            generator: Generator::Random,
+            ..Default::default()
+        };
+        assert!(!Preset::Modern.is_applied(&settings));
+        Preset::Modern.apply(&mut settings);
+        assert!(!settings.enable_question_mark);
+        assert!(settings.enable_flag_chord);
+        assert!(settings.enable_auto_trivial);
+        assert_eq!(settings.generator, Generator::NoRandom);
+        assert!(Preset::Modern.is_applied(&settings));
+        assert!(!Preset::Classic.is_applied(&settings));
+        assert!(Preset::Modern.is_applied(&Settings::default()));
+    }
+
+    #[test]
+    fn classic_preset_starts_safe() {
+        let mut settings = Settings::default();
+        Preset::Classic.apply(&mut settings);
+        assert!(settings.enable_question_mark);
+        assert!(!settings.enable_flag_chord);
+        assert_eq!(settings.generator, Generator::SafeStart);
+        assert_eq!(settings.generator.kind(), game::GeneratorKind::SafeStart);
+        assert!(Preset::Classic.is_applied(&settings));
+    }
+}
@@ -42,6 +42,8 @@ pub(crate) fn format_for_counter(num: i32) -> String {
 
 pub(crate) trait StorageKey {
     const KEY: &'static str;
+    /// Keys used by older versions, newest first, the first one found is moved to `KEY` when `KEY` is missing
+    const LEGACY_KEYS: &'static [&'static str] = &[];
 }
 
 impl<T> StorageKey for Option<T>
@@ -49,6 +51,37 @@ where
     T: StorageKey,
 {
     const KEY: &'static str = T::KEY;
+    const LEGACY_KEYS: &'static [&'static str] = T::LEGACY_KEYS;
+}
+
+/// Move the stored value of a legacy key to `key` unless `key` is already set, the legacy keys are removed afterwards.
+/// The value is moved as is, the type is expected to fill in missing fields when deserializing.
+fn migrate_legacy_keys(key: &str, legacy_keys: &[&str]) {
+    use gloo::storage::{LocalStorage, Storage};
+    if legacy_keys.is_empty() {
+        return;
+    }
+    let storage = LocalStorage::raw();
+    let mut has_key = matches!(storage.get_item(key), Ok(Some(_)));
+    for &legacy_key in legacy_keys {
+        let Ok(Some(value)) = storage.get_item(legacy_key) else {
+            continue;
+        };
+        if !has_key {
+            if let Err(err) = storage.set_item(key, &value) {
+                log::error!(
+                    "Could not migrate local storage key {} to {}: {:?}",
+                    legacy_key,
+                    key,
+                    err
+                );
+                return;
+            }
+            log::info!("Migrated local storage key {} to {}", legacy_key, key);
+            has_key = true;
+        }
+        let _ = storage.remove_item(legacy_key);
+    }
 }
 
 /// Easily load values from local storage
@@ -62,6 +95,7 @@ where
 {
     fn local_or_default() -> Self {
         use gloo::storage::{LocalStorage, Storage};
+        migrate_legacy_keys(Self::KEY, Self::LEGACY_KEYS);
         LocalStorage::get(Self::KEY).unwrap_or_default()
     }
 }
@@ -86,3 +120,52 @@ where
         }
     }
 }
+
+/// Like [`StorageKey`] but with one entry per slot, the full key is `{PREFIX}:{slot}`
+pub(crate) trait SlotStorageKey {
+    const PREFIX: &'static str;
+
+    fn slot_key(slot: &str) -> String {
+        format!("{}:{}", Self::PREFIX, slot)
+    }
+}
+
+impl<T> SlotStorageKey for Option<T>
+where
+    T: SlotStorageKey,
+{
+    const PREFIX: &'static str = T::PREFIX;
+}
+
+/// Easily load values from a local storage slot
+pub(crate) trait SlotLocalOrDefault: Default + SlotStorageKey {
+    fn slot_local_or_default(slot: &str) -> Self;
+}
+
+impl<T> SlotLocalOrDefault for T
+where
+    T: for<'a> serde::Deserialize<'a> + Default + SlotStorageKey,
+{
+    fn slot_local_or_default(slot: &str) -> Self {
+        use gloo::storage::{LocalStorage, Storage};
+        LocalStorage::get(Self::slot_key(slot)).unwrap_or_default()
+    }
+}
+
+/// Easily save values to a local storage slot
+pub(crate) trait SlotLocalSave: Clone + SlotStorageKey {
+    fn slot_local_save(&self, slot: &str);
+}
+
+impl<T> SlotLocalSave for T
+where
+    T: serde::Serialize + Clone + SlotStorageKey,
+{
+    fn slot_local_save(&self, slot: &str) {
+        use gloo::storage::{LocalStorage, Storage};
+        let key = Self::slot_key(slot);
+        if let Err(err) = LocalStorage::set(&key, self.clone()) {
+            log::error!("Could not save to local storage key {}: {:?}", key, err);
+        }
+    }
+}